@@ -7,6 +7,7 @@ pub mod taper;
 pub mod wav;
 pub mod modurate;
 pub mod conv;
+pub mod ir;
 
 // default parameters
 pub const AMP_MIN: f64 = 0.0;
@@ -18,8 +19,25 @@ pub const LOW_FREQ_TSP_DEF: i32 = 20; // Hz
 pub const HIGH_FREQ_TSP_DEF: i32 = 16_000; // Hz
 pub const PWM_FREQ_DEF: i32 = 200; // Hz
 pub const PWM_DUTY_DEF: u32 = 10; // %
+pub const SQUARE_DUTY_DEF: f64 = 50.0; // %
+pub const TRIANGLE_SKEW_DEF: f64 = 50.0; // %
+pub const MULTITONE_NUM_DEF: usize = 31;
+pub const STEPPED_NUM_DEF: usize = 31;
+pub const STEPPED_DWELL_DEF: &str = "1"; // sec
+pub const MLS_ORDER_DEF: u32 = 16;
+pub const GOLAY_LENGTH_DEF: usize = 4096;
+pub const GOLAY_GAP_DEF: &str = "500msec";
+pub const CLICKS_RATE_DEF: &str = "1"; // Hz
+pub const DTMF_TONE_DEF: &str = "100msec";
+pub const DTMF_PAUSE_DEF: &str = "100msec";
+pub const BURST_CYCLES_DEF: f64 = 10.0;
 pub const FS_DEF: f64 = 44_100.0; // Hz
+pub const A4_DEF: f64 = 440.0; // Hz
+pub const BITS_DEF: &str = "16"; // bits per sample
 pub const LEN_TAPER_DEF: usize = 4096; //points
+pub const MOD_FREQ_DEF: i32 = 100; // Hz
+pub const MOD_DEPTH_DEF: f64 = 1.0;
+pub const MOD_INDEX_DEF: f64 = 1.0;
 
 /// A tool for generating WAV files of various signal types.
 #[derive(Parser, Debug)]
@@ -32,7 +50,7 @@ pub struct Cli {
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// generate a wav file
-    Gen(gen::GenOptions),
+    Gen(Box<gen::GenOptions>),
 
     /// apply taper processing on existing wav file
     Taper(taper::TaperOptions),
@@ -45,4 +63,7 @@ pub enum Commands {
 
     /// convolution WAV files.
     Conv(conv::ConvOptions),
+
+    /// measure an impulse response from a recorded TSP or Swept-Sine
+    Ir(ir::IrOptions),
 }
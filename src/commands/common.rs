@@ -19,21 +19,43 @@ pub struct CommonOptions {
     )]
     pub size_of_file: Option<String>,
 
-    /// the maximum absolute value of the signal samplitude
+    /// the maximum absolute value of the signal samplitude.
+    /// linear (0.5), dBFS peak (-20dB) or dBFS RMS (-18dBrms)
     #[arg(
         short, long,
-        default_value_t = super::AMP_DEF,
+        default_value_t = super::AMP_DEF.to_string(),
+        allow_hyphen_values = true,
     )]
-    pub amplitude: f64,
+    pub amplitude: String,
 
-    /// Which channel generate
+    /// Which channel generate.
+    /// all, L, R, LR, or a comma separated list of channel numbers (1, 2, ...) or speakers (FL, LFE, ...)
     #[arg(
         short, long,
-        default_value = "LR",
-        value_parser = ["L", "R", "LR"],
+        default_value = "all",
     )]
     pub channels: String,
 
+    /// Channel layout of the output.
+    /// mono, stereo, 2.1, quad, 5.1, 7.1, 5.1.2, 5.1.4, 7.1.2, 7.1.4 or number of channels
+    #[arg(
+        long,
+        default_value = "stereo",
+    )]
+    pub layout: String,
+
+    /// phase offset of each channel in degrees (e.g. 0,90)
+    #[arg(long)]
+    pub phase: Option<String>,
+
+    /// delay of each channel in seconds or samples (e.g. 0,1msec,48smp)
+    #[arg(long)]
+    pub delay: Option<String>,
+
+    /// channels to invert the polarity (e.g. R, 2,4)
+    #[arg(long)]
+    pub invert: Option<String>,
+
     /// Sample Rate of signal
     #[arg(
         short, long,
@@ -41,6 +63,14 @@ pub struct CommonOptions {
     )]
     pub rate_of_sample: f64,
 
+    /// Bits per sample of the output (32f: 32-bit float)
+    #[arg(
+        short, long,
+        default_value = super::BITS_DEF,
+        value_parser = ["16", "24", "32", "32f"],
+    )]
+    pub bits: String,
+
     /// Output Filename
     #[arg(
         short, long,
@@ -49,14 +79,21 @@ pub struct CommonOptions {
 }
 
 impl CommonOptions {
-    pub fn get_signal_spec(&self, taper_spec: Option<TaperSpec>, d: f64) -> SignalSpec {
-        SignalSpec {
-            amp: super::processing::value_verify(self.amplitude, super::AMP_MIN, super::AMP_MAX),
+    pub fn get_signal_spec(&self, taper_spec: Option<TaperSpec>, d: f64) -> Result<SignalSpec, Box<dyn std::error::Error>> {
+        let (amp, is_rms) = crate::processing::gen::parse_amplitude(&self.amplitude)?;
+        let amp = super::processing::value_verify(amp, super::AMP_MIN, super::AMP_MAX);
+
+        // the peak is scaled after generation to meet the RMS target
+        let (amp, rms) = if is_rms { (super::AMP_MAX, Some(amp)) } else { (amp, None) };
+
+        Ok(SignalSpec {
+            amp,
+            rms,
             ch: self.channels.clone(),
             fs: self.rate_of_sample,
             d: d,
             taper_spec: taper_spec,
-        }
+        })
     }
 }
 
@@ -78,3 +115,58 @@ pub struct TaperSpecOptions {
     )]
     pub window_type: String,
 }
+
+#[derive(Args, Clone, Debug)]
+pub struct TuningOptions {
+    /// frequency of A4 in Hz, the reference of the note names
+    #[arg(
+        long,
+        default_value_t = super::A4_DEF,
+        value_parser = parse_a4,
+    )]
+    pub a4: f64,
+
+    /// tuning system of the note names
+    #[arg(
+        long,
+        default_value = "equal",
+        value_parser = ["equal", "just", "pythagorean"],
+    )]
+    pub tuning: String,
+
+    /// tonic of the just and pythagorean tunings (e.g. C, F#, Bb)
+    #[arg(
+        long,
+        default_value = "C",
+        value_parser = parse_key,
+    )]
+    pub key: String,
+}
+
+fn parse_a4(cmd: &str) -> Result<f64, String> {
+    match cmd.parse::<f64>() {
+        Ok(val) if val > 0.0 => Ok(val),
+        _ => Err(format!("frequency of A4 must be a positive number [{}]", cmd)),
+    }
+}
+
+fn parse_key(cmd: &str) -> Result<String, String> {
+    match crate::processing::gen::parse_key(cmd) {
+        Some(_) => Ok(cmd.to_string()),
+        None => Err(format!("key must be a note name without the octave (e.g. C, F#, Bb) [{}]", cmd)),
+    }
+}
+
+impl TuningOptions {
+    // e.g. _a4-432hz_just-F, empty for the default tuning
+    pub fn get_filename_suffix(&self) -> String {
+        let mut suffix = String::new();
+        if self.a4 != super::A4_DEF {
+            suffix.push_str(&format!("_a4-{}hz", self.a4));
+        }
+        if self.tuning != "equal" {
+            suffix.push_str(&format!("_{}-{}", self.tuning, self.key.replace('#', "s")));
+        }
+        suffix
+    }
+}
@@ -2,6 +2,28 @@ use clap::Args;
 
 #[derive(Args, Debug, Clone)]
 pub struct ConvOptions {
-    #[arg(required = true)]
-    input: String,
+    /// input filename
+    pub input: String,
+
+    /// impulse response filename.
+    /// A mono IR is applied to every channel of the input.
+    pub ir: String,
+
+    /// length of the output signal.
+    /// full: input + IR - 1, same: same as the input
+    #[arg(
+        short, long,
+        default_value = "full",
+        value_parser = ["full", "same"],
+    )]
+    pub mode: String,
+
+    /// normalize the output peak to avoid clipping
+    #[arg(short, long)]
+    pub normalize: bool,
+
+    ///  Output filename.
+    /// If specified without an argument, input file will be overridden.
+    #[arg(short, long)]
+    pub output: Option<Option<String>>,
 }
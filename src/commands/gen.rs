@@ -1,6 +1,7 @@
 use super::common;
 use super::processing;
-use clap::{Args, Subcommand};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap::parser::ValueSource;
 use rtaper::TaperSpec;
 
 const FREQ_DISABLE: f64 = -1.0;
@@ -16,6 +17,9 @@ pub enum WaveFormCommands {
     /// generate a wav file with a sine wave
     Sine(SineOptions),
 
+    /// generate a wav file with a chord of notes
+    Chord(ChordOptions),
+
     /// generate a wav file with a noise
     Noise(NoiseOptions),
 
@@ -28,109 +32,657 @@ pub enum WaveFormCommands {
     /// generate a wav file with a PWM (pulse train)
     Pwm(PwmOptions),
 
+    /// generate a wav file with a square wave
+    Square(SquareOptions),
+
+    /// generate a wav file with a triangle wave
+    Triangle(TriangleOptions),
+
+    /// generate a wav file with a sawtooth wave
+    Sawtooth(SawtoothOptions),
+
+    /// generate a wav file with a multitone (sum of sine waves)
+    Multitone(MultitoneOptions),
+
+    /// generate a wav file with a stepped sine sequence
+    Stepped(SteppedOptions),
+
+    /// generate a wav file with a maximum length sequence (MLS)
+    Mls(MlsOptions),
+
+    /// generate a wav file with a Golay complementary pair
+    Golay(GolayOptions),
+
+    /// generate a wav file with an impulse (Kronecker delta)
+    Impulse(ImpulseOptions),
+
+    /// generate a wav file with a unit step
+    Step(StepOptions),
+
+    /// generate a wav file with a periodic click train
+    Clicks(ClicksOptions),
+
+    /// generate a wav file with DTMF or call-progress tones
+    Dtmf(DtmfOptions),
+
+    /// generate a wav file with an intermodulation distortion test signal
+    Imd(ImdOptions),
+
+    /// generate a wav file with windowed tone bursts
+    Burst(BurstOptions),
+
     /// generate a wav file with zeros
     Zeros(ZerosOptions),
+
+    /// generate a wav file with an independent signal on each channel
+    Multi(MultiOptions),
+}
+
+impl NoiseOptions {
+    pub fn get_band(&self, fs: f64) -> Result<Option<(f64, f64)>, Box<dyn std::error::Error>> {
+        let band = match (&self.band, &self.startf, &self.endf) {
+            (Some(band), _, _) => crate::processing::gen::parse_band(band)?,
+            (None, None, None) => return Ok(None),
+            (None, s, e) => {
+                let s = match s { Some(f) => crate::processing::gen::parse_tuned_freq(f, &self.tuning)?, None => 0.0 };
+                let e = match e { Some(f) => crate::processing::gen::parse_tuned_freq(f, &self.tuning)?, None => fs / 2.0 };
+                (s, e)
+            }
+        };
+
+        if band.0 >= band.1 || band.1 > fs / 2.0 {
+            return Err(format!("invalid band {} Hz to {} Hz", band.0, band.1).into());
+        }
+
+        Ok(Some(band))
+    }
+}
+
+impl MultitoneOptions {
+    // sorted tone frequencies, snapped to the FFT bins if fft_size is given
+    pub fn get_frequencies(&self, fs: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+        let mut freqs = match &self.frequencies {
+            Some(list) => crate::processing::gen::parse_freq_list(list, &self.tuning)?,
+            None => {
+                let s = crate::processing::gen::parse_tuned_freq(&self.startf, &self.tuning)?;
+                let e = crate::processing::gen::parse_tuned_freq(&self.endf, &self.tuning)?;
+                if self.spacing == "log" && (self.type_of_phase == "schroeder" || self.type_of_phase == "newman") {
+                    return Err(format!("{} phases need linearly spaced tones", self.type_of_phase).into());
+                }
+                crate::processing::gen::spaced_frequencies(s, e, self.num_tones, &self.spacing)?
+            }
+        };
+
+        if let Some(n) = self.fft_size {
+            freqs = crate::processing::gen::snap_to_fft_bins(&freqs, fs, n)?;
+        }
+
+        freqs.sort_by(|a, b| a.total_cmp(b));
+        freqs.dedup();
+
+        if freqs.is_empty() {
+            return Err("no tone is specified".into());
+        }
+        if let Some(f) = freqs.iter().find(|&&f| f <= 0.0 || f >= fs / 2.0) {
+            return Err(format!("tone frequency {} Hz is out of range", f).into());
+        }
+
+        Ok(freqs)
+    }
+}
+
+impl SteppedOptions {
+    // the steps are in the order of the list, log-spaced steps are ascending
+    pub fn get_frequencies(&self, fs: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+        let freqs = match &self.frequencies {
+            Some(list) => crate::processing::gen::parse_freq_list(list, &self.tuning)?,
+            None => {
+                let s = crate::processing::gen::parse_tuned_freq(&self.startf, &self.tuning)?;
+                let e = crate::processing::gen::parse_tuned_freq(&self.endf, &self.tuning)?;
+                crate::processing::gen::spaced_frequencies(s, e, self.num_steps, "log")?
+            }
+        };
+
+        if freqs.is_empty() {
+            return Err("no step is specified".into());
+        }
+        if let Some(f) = freqs.iter().find(|&&f| f <= 0.0 || f >= fs / 2.0) {
+            return Err(format!("step frequency {} Hz is out of range", f).into());
+        }
+
+        Ok(freqs)
+    }
+
+    pub fn get_num_steps(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        match &self.frequencies {
+            Some(list) => Ok(crate::processing::gen::parse_freq_list(list, &self.tuning)?.len()),
+            None => Ok(self.num_steps),
+        }
+    }
+
+    pub fn get_dwells(&self, num_steps: usize) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+        let dwells = self.dwell.split(',')
+            .map(|d| crate::processing::gen::parse_duration(d.trim()))
+            .collect::<Result<Vec<f64>, _>>()?;
+
+        match dwells.len() {
+            1 => Ok(vec![dwells[0]; num_steps]),
+            n if n == num_steps => Ok(dwells),
+            n => Err(format!("{} dwell times are given for {} steps", n, num_steps).into()),
+        }
+    }
+
+    // the duration of the sequence is determined by the steps, not by --duration
+    pub fn get_total_duration(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        let num_steps = self.get_num_steps()?;
+        let gap = crate::processing::gen::parse_duration(&self.gap)?;
+        let dwell = self.get_dwells(num_steps)?.iter().sum::<f64>();
+        Ok(dwell + gap * num_steps.saturating_sub(1) as f64)
+    }
+}
+
+impl MlsOptions {
+    // the duration of the MLS is determined by the order and the periods
+    pub fn get_total_duration(&self) -> f64 {
+        (((1_usize << self.order) - 1) * self.periods) as f64 / self.options.rate_of_sample
+    }
+}
+
+impl GolayOptions {
+    // the duration is determined by the length and the gap, that of one sequence if separated
+    pub fn get_total_duration(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        let fs = self.options.rate_of_sample;
+        if self.separate {
+            return Ok(self.length as f64 / fs);
+        }
+        let gap = crate::processing::gen::parse_duration(&self.gap)?;
+        Ok((2 * self.length + (gap * fs) as usize) as f64 / fs)
+    }
+}
+
+impl SweepOptions {
+    // the duration of the sync sweep is adjusted to the integer-compatible sweep rate
+    pub fn get_duration(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        let d = crate::processing::gen::parse_duration(&self.options.duration)?;
+        if self.type_of_sweep != "sync" {
+            return Ok(d);
+        }
+
+        let s = crate::processing::gen::parse_tuned_freq(&self.startf, &self.tuning)?;
+        let e = crate::processing::gen::parse_tuned_freq(&self.endf, &self.tuning)?;
+        Ok(crate::processing::gen::sync_sweep_duration(s, e, d))
+    }
+}
+
+impl TspOptions {
+    pub fn get_band(&self, fs: f64) -> Result<Option<(f64, f64)>, Box<dyn std::error::Error>> {
+        if self.startf.is_none() && self.endf.is_none() {
+            return Ok(None);
+        }
+
+        let s = match &self.startf { Some(f) => crate::processing::gen::parse_tuned_freq(f, &self.tuning)?, None => 0.0 };
+        let e = match &self.endf { Some(f) => crate::processing::gen::parse_tuned_freq(f, &self.tuning)?, None => fs / 2.0 };
+        if s >= e || e > fs / 2.0 {
+            return Err(format!("invalid band {} Hz to {} Hz", s, e).into());
+        }
+
+        Ok(Some((s, e)))
+    }
+
+    // one period of the TSP is exactly N samples if the length or the repetition is given
+    pub fn is_periodic(&self) -> bool {
+        self.length.is_some() || self.repeat > 1
+    }
+
+    pub fn get_length(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        match self.length {
+            Some(n) if n < 4 || n % 2 != 0 => Err(format!("length of the TSP must be an even number of 4 or more, but {}", n).into()),
+            Some(n) => Ok(n),
+            None => {
+                let n_samples = crate::processing::gen::parse_duration(&self.options.duration)? * self.options.rate_of_sample;
+                Ok(1 << n_samples.log2().ceil() as i32)
+            }
+        }
+    }
+
+    pub fn get_total_duration(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        if !self.is_periodic() {
+            return crate::processing::gen::parse_duration(&self.options.duration);
+        }
+        Ok((self.get_length()? * self.repeat) as f64 / self.options.rate_of_sample)
+    }
+}
+
+impl DtmfOptions {
+    // the duration of the digits is determined by the number of the tones
+    pub fn get_total_duration(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        match (&self.preset, &self.digits) {
+            (None, Some(digits)) => {
+                let n = digits.chars().count() as f64;
+                let tone = crate::processing::gen::parse_duration(&self.tone)?;
+                let pause = crate::processing::gen::parse_duration(&self.pause)?;
+                Ok(n * tone + (n - 1.0).max(0.0) * pause)
+            }
+            _ => crate::processing::gen::parse_duration(&self.options.duration),
+        }
+    }
+
+    // * and # are replaced with s and h in the filename
+    pub fn get_filename_type(&self) -> String {
+        match (&self.preset, &self.digits) {
+            (Some(preset), _) => format!("{}_{}", preset, self.region),
+            (None, Some(digits)) => {
+                let digits: String = digits.chars()
+                    .map(|c| match c { '*' => 's', '#' => 'h', c => c.to_ascii_lowercase() })
+                    .collect();
+                format!("dtmf_{}", digits)
+            }
+            (None, None) => "dtmf".to_string(),
+        }
+    }
+}
+
+impl ImdOptions {
+    // frequencies of the two tones and their amplitude ratio
+    pub fn get_tones(&self) -> Result<(f64, f64, f64), Box<dyn std::error::Error>> {
+        let (f1, f2, ratio) = match self.preset.as_str() {
+            "smpte" => (60.0, 7_000.0, 4.0),
+            "ccif" => (19_000.0, 20_000.0, 1.0),
+            "dim" => (3_150.0, 15_000.0, 4.0),
+            _ => return Err(format!("unknown IMD preset [{}]", self.preset).into()),
+        };
+
+        let f1 = match &self.f1 { Some(f) => crate::processing::gen::parse_tuned_freq(f, &self.tuning)?, None => f1 };
+        let f2 = match &self.f2 { Some(f) => crate::processing::gen::parse_tuned_freq(f, &self.tuning)?, None => f2 };
+        let ratio = self.ratio.unwrap_or(ratio);
+        if ratio <= 0.0 {
+            return Err("ratio must be greater than zero".into());
+        }
+
+        Ok((f1, f2, ratio))
+    }
+}
+
+impl BurstOptions {
+    pub fn get_cycles(&self) -> f64 {
+        match self.preset.as_deref() {
+            Some("cea2010") => 6.5,
+            _ => self.cycles,
+        }
+    }
+
+    pub fn get_window(&self) -> &str {
+        match self.preset.as_deref() {
+            Some("cea2010") => "hann",
+            _ => &self.window,
+        }
+    }
+
+    pub fn get_filename_type(&self) -> String {
+        match &self.preset {
+            Some(preset) => format!("{}_burst", preset),
+            None => format!("burst{}cyc_{}", self.cycles, self.window),
+        }
+    }
+}
+
+impl ChordOptions {
+    fn get_intervals(&self) -> Option<&'static [i32]> {
+        let intervals: &[i32] = match self.quality.as_deref()? {
+            "maj" => &[0, 4, 7],
+            "min" => &[0, 3, 7],
+            "dim" => &[0, 3, 6],
+            "aug" => &[0, 4, 8],
+            "sus2" => &[0, 2, 7],
+            "sus4" => &[0, 5, 7],
+            "7" => &[0, 4, 7, 10],
+            "maj7" => &[0, 4, 7, 11],
+            "min7" => &[0, 3, 7, 10],
+            _ => return None,
+        };
+        Some(intervals)
+    }
+
+    pub fn get_frequencies(&self) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+        let notes: Vec<&str> = self.notes.split(',').map(|n| n.trim()).collect();
+        match self.get_intervals() {
+            Some(intervals) => {
+                let root = match notes.as_slice() {
+                    [root] => crate::processing::gen::parse_note(root)
+                        .ok_or_else(|| format!("root of the chord must be a note name [{}]", root))?.0,
+                    _ => return Err("quality of the chord requires a single root note".into()),
+                };
+                intervals.iter()
+                    .map(|i| crate::processing::gen::tuned_frequency(root + i, &self.tuning))
+                    .collect()
+            }
+            None => {
+                notes.iter()
+                    .map(|n| crate::processing::gen::parse_tuned_freq(n, &self.tuning))
+                    .collect()
+            }
+        }
+    }
+
+    pub fn get_filename_type(&self) -> String {
+        if let Some(quality) = &self.quality {
+            let root = crate::processing::gen::note_label(self.notes.trim()).unwrap_or_else(|| self.notes.clone());
+            return format!("chord_{}{}{}", root, quality, self.tuning.get_filename_suffix());
+        }
+
+        let names: String = self.notes.split(',')
+            .map(|n| {
+                let f = crate::processing::gen::parse_tuned_freq(n.trim(), &self.tuning).unwrap_or(FREQ_DISABLE);
+                crate::fileio::freq_format(f as i32, crate::processing::gen::note_label(n.trim()).as_deref(), "")
+            })
+            .collect();
+        format!("chord{}{}", names, self.tuning.get_filename_suffix())
+    }
 }
 
 impl WaveFormCommands {
     pub fn get_common_opt(&self) -> &common::CommonOptions {
         match self {
             WaveFormCommands::Sine(opt) => &opt.options,
+            WaveFormCommands::Chord(opt) => &opt.options,
             WaveFormCommands::Noise(opt) => &opt.options,
             WaveFormCommands::Tsp(opt) => &opt.options,
             WaveFormCommands::Sweep(opt) => &opt.options,
             WaveFormCommands::Pwm(opt) => &opt.options,
+            WaveFormCommands::Square(opt) => &opt.options,
+            WaveFormCommands::Triangle(opt) => &opt.options,
+            WaveFormCommands::Sawtooth(opt) => &opt.options,
+            WaveFormCommands::Multitone(opt) => &opt.options,
+            WaveFormCommands::Stepped(opt) => &opt.options,
+            WaveFormCommands::Mls(opt) => &opt.options,
+            WaveFormCommands::Golay(opt) => &opt.options,
+            WaveFormCommands::Impulse(opt) => &opt.options,
+            WaveFormCommands::Step(opt) => &opt.options,
+            WaveFormCommands::Clicks(opt) => &opt.options,
+            WaveFormCommands::Dtmf(opt) => &opt.options,
+            WaveFormCommands::Imd(opt) => &opt.options,
+            WaveFormCommands::Burst(opt) => &opt.options,
             WaveFormCommands::Zeros(opt) => &opt.options,
+            WaveFormCommands::Multi(opt) => &opt.options,
         }
     }
 
     pub fn get_taper_spec(&self) -> Option<TaperSpec> {
         let opt = match self {
             WaveFormCommands::Sine(opt) => Some(&opt.taper_opt),
+            WaveFormCommands::Chord(opt) => Some(&opt.taper_opt),
             WaveFormCommands::Noise(opt) => Some(&opt.taper_opt),
             WaveFormCommands::Tsp(_) => None,
             WaveFormCommands::Sweep(opt) => Some(&opt.taper_opt),
             WaveFormCommands::Pwm(opt) => Some(&opt.taper_opt),
+            WaveFormCommands::Square(opt) => Some(&opt.taper_opt),
+            WaveFormCommands::Triangle(opt) => Some(&opt.taper_opt),
+            WaveFormCommands::Sawtooth(opt) => Some(&opt.taper_opt),
+            WaveFormCommands::Multitone(opt) => Some(&opt.taper_opt),
+            WaveFormCommands::Stepped(opt) => Some(&opt.taper_opt),
+            WaveFormCommands::Mls(_) => None,
+            WaveFormCommands::Golay(_) => None,
+            WaveFormCommands::Impulse(_) => None,
+            WaveFormCommands::Step(_) => None,
+            WaveFormCommands::Clicks(_) => None,
+            WaveFormCommands::Dtmf(_) => None,
+            WaveFormCommands::Imd(opt) => Some(&opt.taper_opt),
+            WaveFormCommands::Burst(_) => None,
             WaveFormCommands::Zeros(_) => None,
+            WaveFormCommands::Multi(_) => None,
         };
 
         processing::gen::get_taper_spec(opt)
     }
 
-    pub fn get_duration_cmd(&self) -> &String {
-        match self {
-            WaveFormCommands::Sine(opt) => &opt.options.duration,
-            WaveFormCommands::Noise(opt) => &opt.options.duration,
-            WaveFormCommands::Tsp(opt) => &opt.options.duration,
-            WaveFormCommands::Sweep(opt) => &opt.options.duration,
-            WaveFormCommands::Pwm(opt) => &opt.options.duration,
-            WaveFormCommands::Zeros(opt) => &opt.options.duration,
-        }
+    // the duration for the filename, signals of their own length give the adjusted duration
+    pub fn get_duration_cmd(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let cmd = match self {
+            WaveFormCommands::Sine(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Chord(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Noise(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Tsp(opt) if opt.is_periodic() => self.get_duration_in_sec()?.to_string(),
+            WaveFormCommands::Tsp(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Sweep(opt) if opt.type_of_sweep == "sync" => self.get_duration_in_sec()?.to_string(),
+            WaveFormCommands::Sweep(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Pwm(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Square(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Triangle(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Sawtooth(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Multitone(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Stepped(_) => self.get_duration_in_sec()?.to_string(),
+            WaveFormCommands::Mls(_) => self.get_duration_in_sec()?.to_string(),
+            WaveFormCommands::Golay(_) => self.get_duration_in_sec()?.to_string(),
+            WaveFormCommands::Impulse(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Step(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Clicks(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Dtmf(opt) if opt.preset.is_none() => self.get_duration_in_sec()?.to_string(),
+            WaveFormCommands::Dtmf(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Imd(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Burst(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Zeros(opt) => opt.options.duration.clone(),
+            WaveFormCommands::Multi(opt) => opt.options.duration.clone(),
+        };
+
+        Ok(cmd)
     }
 
     pub fn get_duration_in_sec(&self) -> Result<f64, Box<dyn std::error::Error>> {
         match self {
             WaveFormCommands::Sine(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Chord(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
             WaveFormCommands::Noise(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
-            WaveFormCommands::Tsp(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
-            WaveFormCommands::Sweep(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Tsp(opt) => opt.get_total_duration(),
+            WaveFormCommands::Sweep(opt) => opt.get_duration(),
             WaveFormCommands::Pwm(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Square(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Triangle(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Sawtooth(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Multitone(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Stepped(opt) => opt.get_total_duration(),
+            WaveFormCommands::Mls(opt) => Ok(opt.get_total_duration()),
+            WaveFormCommands::Golay(opt) => opt.get_total_duration(),
+            WaveFormCommands::Impulse(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Step(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Clicks(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Dtmf(opt) => opt.get_total_duration(),
+            WaveFormCommands::Imd(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Burst(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
             WaveFormCommands::Zeros(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
+            WaveFormCommands::Multi(opt) => crate::processing::gen::parse_duration(&opt.options.duration),
         }
     }
 
     pub fn get_duration_from_filesize(&self, cmd: &str) -> Result<f64, Box<dyn std::error::Error>> {
         match self {
             WaveFormCommands::Sine(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Chord(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
             WaveFormCommands::Noise(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
             WaveFormCommands::Tsp(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
             WaveFormCommands::Sweep(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
             WaveFormCommands::Pwm(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Square(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Triangle(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Sawtooth(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Multitone(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Stepped(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Mls(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Golay(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Impulse(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Step(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Clicks(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Dtmf(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Imd(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Burst(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
             WaveFormCommands::Zeros(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
+            WaveFormCommands::Multi(_) => crate::processing::gen::parse_filesize(cmd, self.get_common_opt()),
         }
     }
 
-    pub fn get_fileinfo(&self, fs: f64) -> (String, f64, f64) {
-        match self {
+    // note names of the start and end frequencies for filenames,
+    // the tuning is appended to the last note if it is not the default
+    pub fn get_note_labels(&self) -> (Option<String>, Option<String>) {
+        let label = |cmd: &str| crate::processing::gen::note_label(cmd);
+        let label_opt = |cmd: &Option<String>| cmd.as_deref().and_then(label);
+        let (labels, tuning) = match self {
+            WaveFormCommands::Sine(opt) => ((label(&opt.frequency), None), &opt.tuning),
+            WaveFormCommands::Sweep(opt) => ((label(&opt.startf), label(&opt.endf)), &opt.tuning),
+            WaveFormCommands::Square(opt) => ((label(&opt.frequency), None), &opt.tuning),
+            WaveFormCommands::Triangle(opt) => ((label(&opt.frequency), None), &opt.tuning),
+            WaveFormCommands::Sawtooth(opt) => ((label(&opt.frequency), None), &opt.tuning),
+            WaveFormCommands::Imd(opt) => ((label_opt(&opt.f1), label_opt(&opt.f2)), &opt.tuning),
+            WaveFormCommands::Burst(opt) => ((label(&opt.frequency), None), &opt.tuning),
+            WaveFormCommands::Noise(opt) => ((label_opt(&opt.startf), label_opt(&opt.endf)), &opt.tuning),
+            WaveFormCommands::Tsp(opt) => ((label_opt(&opt.startf), label_opt(&opt.endf)), &opt.tuning),
+            // the generated tones are from startf to endf unless they are snapped to the FFT bins
+            WaveFormCommands::Multitone(opt) if opt.frequencies.is_none() && opt.fft_size.is_none() => {
+                ((label(&opt.startf), label(&opt.endf)), &opt.tuning)
+            }
+            WaveFormCommands::Stepped(opt) if opt.frequencies.is_none() => ((label(&opt.startf), label(&opt.endf)), &opt.tuning),
+            _ => return (None, None),
+        };
+
+        let suffix = tuning.get_filename_suffix();
+        match labels {
+            (s, Some(e)) => (s, Some(e + &suffix)),
+            (Some(s), None) => (Some(s + &suffix), None),
+            labels => labels,
+        }
+    }
+
+    pub fn get_fileinfo(&self, fs: f64) -> Result<(String, f64, f64), Box<dyn std::error::Error>> {
+        let fileinfo = match self {
             WaveFormCommands::Sine(opt) => {
-                let f = crate::processing::gen::parse_freq(&opt.frequency).unwrap();
+                let f = crate::processing::gen::parse_tuned_freq(&opt.frequency, &opt.tuning)?;
                 let f_verified = super::processing::value_verify(f, 0.0, fs / 2.0);
                 ("sine".to_string(), f_verified, FREQ_DISABLE)
             }
             WaveFormCommands::Noise(opt) => {
                 let noise_type = &opt.noise_type;
-                let filename_type = format!("{}noise", noise_type);
-                (filename_type, FREQ_DISABLE, FREQ_DISABLE)
+                let mut filename_type = if opt.distribution == "uniform" {
+                    format!("{}noise", noise_type)
+                }else {
+                    format!("{}noise_{}", noise_type, opt.distribution)
+                };
+                if let Some(band) = &opt.band {
+                    filename_type = format!("{}_{}", filename_type, band);
+                }
+                // only the given ends of the band are in the filename
+                match opt.get_band(fs)? {
+                    Some((s, e)) if opt.band.is_none() => {
+                        let s = if opt.startf.is_some() { s } else { FREQ_DISABLE };
+                        let e = if opt.endf.is_some() { e } else { FREQ_DISABLE };
+                        (filename_type, s, e)
+                    }
+                    _ => (filename_type, FREQ_DISABLE, FREQ_DISABLE),
+                }
             }
             WaveFormCommands::Tsp(opt) => {
                 let direction = if opt.flip { "up" } else { "down" };
                 let filename_type: String = format!("{}_{}TSP", opt.tsp_type, direction);
-                (filename_type, FREQ_DISABLE, FREQ_DISABLE)
+                match opt.get_band(fs)? {
+                    Some((s, e)) => (filename_type, s, e),
+                    None => (filename_type, FREQ_DISABLE, FREQ_DISABLE),
+                }
             }
             WaveFormCommands::Sweep(opt) => {
                 let filename_type: String = format!("{}_sweep", opt.type_of_sweep);
-                let s = crate::processing::gen::parse_freq(&opt.startf).unwrap();
-                let e = crate::processing::gen::parse_freq(&opt.endf).unwrap();
+                let s = crate::processing::gen::parse_tuned_freq(&opt.startf, &opt.tuning)?;
+                let e = crate::processing::gen::parse_tuned_freq(&opt.endf, &opt.tuning)?;
                 (filename_type, s, e)
             }
             WaveFormCommands::Pwm(opt) => {
                 let f_verified = super::processing::value_verify(opt.frequency as f64, 0.0, fs / 2.0);
                 ("pwm".to_string(), f_verified, FREQ_DISABLE)
             }
+            WaveFormCommands::Square(opt) => {
+                let f = crate::processing::gen::parse_tuned_freq(&opt.frequency, &opt.tuning)?;
+                let f_verified = super::processing::value_verify(f, 0.0, fs / 2.0);
+                ("square".to_string(), f_verified, FREQ_DISABLE)
+            }
+            WaveFormCommands::Triangle(opt) => {
+                let f = crate::processing::gen::parse_tuned_freq(&opt.frequency, &opt.tuning)?;
+                let f_verified = super::processing::value_verify(f, 0.0, fs / 2.0);
+                ("triangle".to_string(), f_verified, FREQ_DISABLE)
+            }
+            WaveFormCommands::Sawtooth(opt) => {
+                let f = crate::processing::gen::parse_tuned_freq(&opt.frequency, &opt.tuning)?;
+                let f_verified = super::processing::value_verify(f, 0.0, fs / 2.0);
+                ("sawtooth".to_string(), f_verified, FREQ_DISABLE)
+            }
+            WaveFormCommands::Multitone(opt) => {
+                let freqs = opt.get_frequencies(fs).unwrap_or_default();
+                match (freqs.first(), freqs.last()) {
+                    (Some(&s), Some(&e)) => ("multitone".to_string(), s, e),
+                    _ => ("multitone".to_string(), FREQ_DISABLE, FREQ_DISABLE),
+                }
+            }
+            WaveFormCommands::Stepped(opt) => {
+                let freqs = opt.get_frequencies(fs).unwrap_or_default();
+                let s = freqs.iter().cloned().reduce(f64::min).unwrap_or(FREQ_DISABLE);
+                let e = freqs.iter().cloned().reduce(f64::max).unwrap_or(FREQ_DISABLE);
+                ("stepped".to_string(), s, e)
+            }
+            WaveFormCommands::Mls(opt) => { (format!("mls{}", opt.order), FREQ_DISABLE, FREQ_DISABLE) }
+            WaveFormCommands::Golay(opt) => { (format!("golay{}", opt.length), FREQ_DISABLE, FREQ_DISABLE) }
+            WaveFormCommands::Impulse(_) => { ("impulse".to_string(), FREQ_DISABLE, FREQ_DISABLE) }
+            WaveFormCommands::Step(_) => { ("step".to_string(), FREQ_DISABLE, FREQ_DISABLE) }
+            WaveFormCommands::Clicks(opt) => {
+                let rate = crate::processing::gen::parse_rate(&opt.rate)?;
+                ("clicks".to_string(), rate, FREQ_DISABLE)
+            }
+            WaveFormCommands::Dtmf(opt) => { (opt.get_filename_type(), FREQ_DISABLE, FREQ_DISABLE) }
+            WaveFormCommands::Imd(opt) => {
+                let (f1, f2, _) = opt.get_tones()?;
+                (format!("imd_{}", opt.preset), f1, f2)
+            }
+            WaveFormCommands::Burst(opt) => {
+                let f = crate::processing::gen::parse_tuned_freq(&opt.frequency, &opt.tuning)?;
+                (opt.get_filename_type(), f, FREQ_DISABLE)
+            }
+            WaveFormCommands::Chord(opt) => { (opt.get_filename_type(), FREQ_DISABLE, FREQ_DISABLE) }
             WaveFormCommands::Zeros(_) => { ("zeros".to_string(), FREQ_DISABLE, FREQ_DISABLE) }
-        }
+            WaveFormCommands::Multi(_) => { ("multi".to_string(), FREQ_DISABLE, FREQ_DISABLE) }
+        };
+
+        Ok(fileinfo)
     }
 }
 
 #[derive(Args, Debug, Clone)]
 pub struct SineOptions {
-    /// Frequency of the sine wave in Hz
+    /// Frequency of the sine wave in Hz or a note name (e.g. A4, C#3, Bb2)
     #[arg(
         short, long,
         default_value_t = super::FREQ_DEF.to_string(),
     )]
     pub frequency: String,
 
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+
+    #[command(flatten)]
+    pub taper_opt: common::TaperSpecOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ChordOptions {
+    /// comma separated list of notes or frequencies in Hz (e.g. C4,E4,G4), or the root of the quality
+    pub notes: String,
+
+    /// quality of the chord built on the root note
+    #[arg(
+        short, long,
+        value_parser = ["maj", "min", "dim", "aug", "sus2", "sus4", "7", "maj7", "min7"],
+    )]
+    pub quality: Option<String>,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
     #[command(flatten)]
     pub options: common::CommonOptions,
 
@@ -144,10 +696,43 @@ pub struct NoiseOptions {
     #[arg(
         short, long,
         default_value = "white",
-        value_parser = ["white", "pink"],
+        value_parser = ["white", "pink", "brown", "red", "blue", "violet", "grey"],
     )]
     pub noise_type: String,
 
+    /// amplitude distribution of the noise
+    #[arg(
+        long,
+        default_value = "uniform",
+        value_parser = ["uniform", "gaussian", "laplacian", "binary"],
+    )]
+    pub distribution: String,
+
+    /// maximum crest factor (peak / RMS) as a ratio or in dB (e.g. 4, 12dB).
+    /// peaks above the crest factor are clipped.
+    #[arg(long)]
+    pub crest_factor: Option<String>,
+
+    /// Starting frequency of the band-limited noise in Hz
+    #[arg(long, conflicts_with = "band")]
+    pub startf: Option<String>,
+
+    /// Ending frequency of the band-limited noise in Hz
+    #[arg(long, conflicts_with = "band")]
+    pub endf: Option<String>,
+
+    /// octave or third octave band of the noise (e.g. 1k-oct, 1k-third)
+    #[arg(long)]
+    pub band: Option<String>,
+
+    /// seed of the random number generator.
+    /// If omitted, a random seed is used. The seed is recorded in the output file.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
     #[command(flatten)]
     pub options: common::CommonOptions,
 
@@ -172,15 +757,40 @@ pub struct TspOptions {
     /// convert to down TSP by flipping the time axis
     #[arg(short, long)]
     pub flip: bool,
+
+    /// Starting frequency of the band of the TSP in Hz
+    #[arg(long)]
+    pub startf: Option<String>,
+
+    /// Ending frequency of the band of the TSP in Hz
+    #[arg(long)]
+    pub endf: Option<String>,
+
+    /// length N of one period of the TSP in samples.
+    /// If omitted, the duration is rounded up to a power of two
+    #[arg(short = 'n', long)]
+    pub length: Option<usize>,
+
+    /// number of the repeated periods.
+    /// with --length or --repeat, each period is exactly N samples
+    #[arg(
+        long,
+        default_value_t = 1,
+    )]
+    pub repeat: usize,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
 }
 
 #[derive(Args, Debug , Clone)]
 pub struct SweepOptions {
-    /// type of Swept-Sine waveform
+    /// type of Swept-Sine waveform.
+    /// sync is the synchronized log sweep (Novák), its duration is adjusted
     #[arg(
         short, long,
         default_value = "linear",
-        value_parser = ["linear", "log"],
+        value_parser = ["linear", "log", "sync"],
     )]
     pub type_of_sweep: String,
 
@@ -198,6 +808,35 @@ pub struct SweepOptions {
     )]
     pub endf : String,
 
+    /// also write the inverse filter to <OUTPUT>_inverse.wav
+    #[arg(long)]
+    pub inverse: bool,
+
+    /// fade-in from the starting frequency to this frequency in Hz
+    #[arg(long)]
+    pub fade_in: Option<String>,
+
+    /// fade-out from this frequency to the ending frequency in Hz
+    #[arg(long)]
+    pub fade_out: Option<String>,
+
+    /// silence before the sweep in seconds or samples (e.g. 100msec, 4800smp)
+    #[arg(
+        long,
+        default_value = "0",
+    )]
+    pub pre_silence: String,
+
+    /// silence after the sweep in seconds or samples
+    #[arg(
+        long,
+        default_value = "0",
+    )]
+    pub post_silence: String,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
     #[command(flatten)]
     pub options: common::CommonOptions,
 
@@ -228,8 +867,540 @@ pub struct PwmOptions {
     pub taper_opt: common::TaperSpecOptions,
 }
 
+#[derive(Args, Debug, Clone)]
+pub struct SquareOptions {
+    /// Frequency of the square wave in Hz
+    #[arg(
+        short, long,
+        default_value_t = super::FREQ_DEF.to_string(),
+    )]
+    pub frequency: String,
+
+    /// Duty cycle of the square wave in %
+    #[arg(
+        short, long,
+        default_value_t = super::SQUARE_DUTY_DEF,
+    )]
+    pub percent_of_duty: f64,
+
+    /// synthesis method. polyblep and additive are band-limited
+    #[arg(
+        short, long,
+        default_value = "polyblep",
+        value_parser = ["naive", "polyblep", "additive"],
+    )]
+    pub method: String,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+
+    #[command(flatten)]
+    pub taper_opt: common::TaperSpecOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct TriangleOptions {
+    /// Frequency of the triangle wave in Hz
+    #[arg(
+        short, long,
+        default_value_t = super::FREQ_DEF.to_string(),
+    )]
+    pub frequency: String,
+
+    /// rising part of a period in % (50: symmetric, 100: sawtooth)
+    #[arg(
+        short = 'k', long,
+        default_value_t = super::TRIANGLE_SKEW_DEF,
+    )]
+    pub skew: f64,
+
+    /// synthesis method. polyblep and additive are band-limited
+    #[arg(
+        short, long,
+        default_value = "polyblep",
+        value_parser = ["naive", "polyblep", "additive"],
+    )]
+    pub method: String,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+
+    #[command(flatten)]
+    pub taper_opt: common::TaperSpecOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SawtoothOptions {
+    /// Frequency of the sawtooth wave in Hz
+    #[arg(
+        short, long,
+        default_value_t = super::FREQ_DEF.to_string(),
+    )]
+    pub frequency: String,
+
+    /// synthesis method. polyblep and additive are band-limited
+    #[arg(
+        short, long,
+        default_value = "polyblep",
+        value_parser = ["naive", "polyblep", "additive"],
+    )]
+    pub method: String,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+
+    #[command(flatten)]
+    pub taper_opt: common::TaperSpecOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct MultitoneOptions {
+    /// comma separated list of the tone frequencies in Hz (e.g. 100,1k,10k).
+    /// If omitted, tones from startf to endf are generated.
+    #[arg(short, long)]
+    pub frequencies: Option<String>,
+
+    /// spacing of the generated tones
+    #[arg(
+        long,
+        default_value = "log",
+        value_parser = ["log", "linear"],
+    )]
+    pub spacing: String,
+
+    /// Starting frequency of the generated tones in Hz
+    #[arg(
+        long,
+        default_value_t = super::LOW_FREQ_TSP_DEF.to_string(),
+    )]
+    pub startf: String,
+
+    /// Ending frequency of the generated tones in Hz
+    #[arg(
+        long,
+        default_value_t = super::HIGH_FREQ_TSP_DEF.to_string(),
+    )]
+    pub endf: String,
+
+    /// number of the generated tones
+    #[arg(
+        short, long,
+        default_value_t = super::MULTITONE_NUM_DEF,
+    )]
+    pub num_tones: usize,
+
+    /// phases of the tones. schroeder and newman lower the crest factor of linearly spaced tones
+    #[arg(
+        short, long,
+        default_value = "random",
+        value_parser = ["zero", "random", "schroeder", "newman"],
+    )]
+    pub type_of_phase: String,
+
+    /// seed of the random phases
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// snap the tones to the FFT bins of this block size, so that the signal is periodic.
+    /// the taper is not applied
+    #[arg(long)]
+    pub fft_size: Option<usize>,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+
+    #[command(flatten)]
+    pub taper_opt: common::TaperSpecOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SteppedOptions {
+    /// comma separated list of the step frequencies in Hz (e.g. 100,1k,10k).
+    /// If omitted, log-spaced steps from startf to endf are generated.
+    #[arg(short, long)]
+    pub frequencies: Option<String>,
+
+    /// Starting frequency of the log-spaced steps in Hz
+    #[arg(
+        long,
+        default_value_t = super::LOW_FREQ_TSP_DEF.to_string(),
+    )]
+    pub startf: String,
+
+    /// Ending frequency of the log-spaced steps in Hz
+    #[arg(
+        long,
+        default_value_t = super::HIGH_FREQ_TSP_DEF.to_string(),
+    )]
+    pub endf: String,
+
+    /// number of the log-spaced steps
+    #[arg(
+        short, long,
+        default_value_t = super::STEPPED_NUM_DEF,
+    )]
+    pub num_steps: usize,
+
+    /// dwell time of each step in seconds.
+    /// a single value for all steps or a comma separated list (e.g. 2,1,500msec)
+    #[arg(
+        long,
+        default_value = super::STEPPED_DWELL_DEF,
+    )]
+    pub dwell: String,
+
+    /// silence between the steps in seconds
+    #[arg(
+        long,
+        default_value = "0",
+    )]
+    pub gap: String,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+
+    #[command(flatten)]
+    pub taper_opt: common::TaperSpecOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct MlsOptions {
+    /// order of the MLS, the length of one period is 2^order - 1
+    #[arg(
+        short = 'n', long,
+        default_value_t = super::MLS_ORDER_DEF,
+        value_parser = clap::value_parser!(u32).range(2..=24),
+    )]
+    pub order: u32,
+
+    /// feedback taps of the shift register including the order (e.g. 16,15,13,4).
+    /// If omitted, a primitive polynomial of the order is used.
+    #[arg(long)]
+    pub taps: Option<String>,
+
+    /// number of the repeated periods
+    #[arg(
+        short, long,
+        default_value_t = 1,
+    )]
+    pub periods: usize,
+
+    /// seed of the initial state of the shift register
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct GolayOptions {
+    /// length of each sequence of the pair (power of two)
+    #[arg(
+        short = 'n', long,
+        default_value_t = super::GOLAY_LENGTH_DEF,
+    )]
+    pub length: usize,
+
+    /// silence between the sequence A and B
+    #[arg(
+        long,
+        default_value = super::GOLAY_GAP_DEF,
+    )]
+    pub gap: String,
+
+    /// write the sequence A and B to the separate files (<OUTPUT>_a.wav, <OUTPUT>_b.wav)
+    #[arg(long)]
+    pub separate: bool,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ImpulseOptions {
+    /// position of the impulse in seconds or samples (e.g. 10msec, 480smp)
+    #[arg(
+        short, long,
+        default_value = "0",
+    )]
+    pub position: String,
+
+    /// width of the impulse in samples
+    #[arg(
+        long,
+        default_value_t = 1,
+    )]
+    pub width: usize,
+
+    /// polarity of the impulse
+    #[arg(
+        long,
+        default_value = "positive",
+        value_parser = ["positive", "negative"],
+    )]
+    pub polarity: String,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct StepOptions {
+    /// position of the step in seconds or samples (e.g. 10msec, 480smp)
+    #[arg(
+        short, long,
+        default_value = "0",
+    )]
+    pub position: String,
+
+    /// polarity of the step
+    #[arg(
+        long,
+        default_value = "positive",
+        value_parser = ["positive", "negative"],
+    )]
+    pub polarity: String,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ClicksOptions {
+    /// rate of the clicks in Hz or BPM (e.g. 2, 120bpm)
+    #[arg(
+        short = 'f', long,
+        default_value = super::CLICKS_RATE_DEF,
+    )]
+    pub rate: String,
+
+    /// width of each click in samples
+    #[arg(
+        long,
+        default_value_t = 1,
+    )]
+    pub width: usize,
+
+    /// polarity of the clicks
+    #[arg(
+        long,
+        default_value = "positive",
+        value_parser = ["positive", "negative"],
+    )]
+    pub polarity: String,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DtmfOptions {
+    /// digits to dial (0-9, *, #, A-D)
+    #[arg(required_unless_present = "preset")]
+    pub digits: Option<String>,
+
+    /// duration of each tone
+    #[arg(
+        long,
+        default_value = super::DTMF_TONE_DEF,
+    )]
+    pub tone: String,
+
+    /// pause between the tones
+    #[arg(
+        long,
+        default_value = super::DTMF_PAUSE_DEF,
+    )]
+    pub pause: String,
+
+    /// call-progress tone instead of the digits, repeated for the duration
+    #[arg(
+        short, long,
+        value_parser = ["dial", "busy", "ringback"],
+        conflicts_with = "digits",
+    )]
+    pub preset: Option<String>,
+
+    /// region of the call-progress tone
+    #[arg(
+        long,
+        default_value = "us",
+        value_parser = ["us", "eu", "uk", "jp"],
+    )]
+    pub region: String,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ImdOptions {
+    /// standard test signal.
+    /// smpte: 60Hz + 7kHz at 4:1, ccif: 19kHz + 20kHz at 1:1, dim: 3.15kHz square + 15kHz sine at 4:1
+    #[arg(
+        short, long,
+        default_value = "smpte",
+        value_parser = ["smpte", "ccif", "dim"],
+    )]
+    pub preset: String,
+
+    /// overrides the frequency of the first tone (the square wave of dim) in Hz
+    #[arg(long)]
+    pub f1: Option<String>,
+
+    /// overrides the frequency of the second tone in Hz
+    #[arg(long)]
+    pub f2: Option<String>,
+
+    /// overrides the amplitude ratio of the first tone to the second tone
+    #[arg(long)]
+    pub ratio: Option<f64>,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+
+    #[command(flatten)]
+    pub taper_opt: common::TaperSpecOptions,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct BurstOptions {
+    /// Frequency of the burst in Hz
+    #[arg(
+        short, long,
+        default_value_t = super::FREQ_DEF.to_string(),
+    )]
+    pub frequency: String,
+
+    /// number of cycles in each burst
+    #[arg(
+        short = 'n', long,
+        default_value_t = super::BURST_CYCLES_DEF,
+    )]
+    pub cycles: f64,
+
+    /// window of each burst (rect: no window)
+    #[arg(
+        long,
+        default_value = "hann",
+        value_parser = ["rect", "linear", "hann", "cos", "blackman"],
+    )]
+    pub window: String,
+
+    /// repetition period of the bursts, a single burst if not set
+    #[arg(short, long)]
+    pub period: Option<String>,
+
+    /// standard burst instead of the cycles and the window.
+    /// cea2010: 6.5 cycles of the Hann-windowed sine
+    #[arg(
+        long,
+        value_parser = ["cea2010"],
+        conflicts_with_all = ["cycles", "window"],
+    )]
+    pub preset: Option<String>,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+}
+
 #[derive(Args, Debug, Clone)]
 pub struct ZerosOptions {
     #[command(flatten)]
     pub options: common::CommonOptions,
 }
+
+const BREAK_PARSE_CHANNEL: &str = "--ch";
+
+// options of the output file are given by multi, they are rejected after --ch
+const MULTI_FILE_OPTIONS: [&str; 12] = [
+    "duration", "size_of_file", "rate_of_sample", "bits", "layout", "channels",
+    "phase", "delay", "invert", "output_filename", "inverse", "separate",
+];
+
+#[derive(Args, Debug, Clone)]
+pub struct MultiOptions {
+    /// signal of each channel: --ch <CHANNELS> <WAVEFORM> [OPTIONS] ...
+    /// duration, sample rate, bits, layout, channels and output of the signals are given by the options of multi.
+    #[arg(
+        required = true,
+        trailing_var_arg = true,
+        allow_hyphen_values = true,
+    )]
+    pub signals: Vec<String>,
+
+    #[command(flatten)]
+    pub options: common::CommonOptions,
+}
+
+// parser of the waveform following --ch <CHANNELS>
+#[derive(Parser, Debug)]
+#[command(name = BREAK_PARSE_CHANNEL, no_binary_name = true)]
+struct ChannelSignal {
+    #[command(subcommand)]
+    waveform: WaveFormCommands,
+}
+
+impl MultiOptions {
+    pub fn parse_commands(&self) -> Result<Vec<(String, WaveFormCommands)>, Box<dyn std::error::Error>> {
+        let mut signals = Vec::new();
+        let mut iter = self.signals.iter().peekable();
+
+        while let Some(input_str) = iter.next() {
+            if input_str != BREAK_PARSE_CHANNEL {
+                return Err(format!("{} is expected, but [{}] is given", BREAK_PARSE_CHANNEL, input_str).into());
+            }
+
+            let channels = iter.next().ok_or("channels are not given after --ch")?;
+
+            let mut waveform_commands: Vec<&String> = Vec::new();
+            while let Some(cmd) = iter.next_if(|cmd| *cmd != BREAK_PARSE_CHANNEL) {
+                waveform_commands.push(cmd);
+            }
+
+            let matches = ChannelSignal::command()
+                .try_get_matches_from(waveform_commands)
+                .map_err(|e| format!("--ch {}: {}", channels, e))?;
+            if let Some((_, sub_matches)) = matches.subcommand() {
+                let given = sub_matches.ids()
+                    .map(|id| id.as_str())
+                    .find(|id| MULTI_FILE_OPTIONS.contains(id) && sub_matches.value_source(id) == Some(ValueSource::CommandLine));
+                if let Some(id) = given {
+                    return Err(format!("--ch {}: --{} is not available for a channel of multi", channels, id.replace('_', "-")).into());
+                }
+            }
+
+            let signal = ChannelSignal::from_arg_matches(&matches)
+                .map_err(|e| format!("--ch {}: {}", channels, e))?;
+            if let WaveFormCommands::Multi(_) = signal.waveform {
+                return Err("multi cannot be nested".into());
+            }
+
+            signals.push((channels.clone(), signal.waveform));
+        }
+
+        Ok(signals)
+    }
+}
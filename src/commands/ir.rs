@@ -0,0 +1,102 @@
+use clap::Args;
+use super::common;
+
+#[derive(Args, Debug, Clone)]
+pub struct IrOptions {
+    /// recorded response filename
+    pub recording: String,
+
+    /// stimulus wav file.
+    /// If omitted, the stimulus is generated from the options below.
+    #[arg(short, long)]
+    pub stimulus: Option<String>,
+
+    /// type of the stimulus signal
+    #[arg(
+        short, long,
+        default_value = "log_tsp",
+        value_parser = ["log_tsp", "linear_tsp", "log_sweep", "linear_sweep", "sync_sweep", "mls"],
+    )]
+    pub type_of_stimulus: String,
+
+    /// duration of the stimulus in seconds.
+    #[arg(
+        short, long,
+        default_value_t = super::D_DEF.to_string(),
+    )]
+    pub duration: String,
+
+    /// the maximum absolute value of the stimulus amplitude
+    #[arg(
+        short, long,
+        default_value_t = super::AMP_DEF,
+    )]
+    pub amplitude: f64,
+
+    /// the stimulus is an up TSP (flipped)
+    #[arg(short, long)]
+    pub flip: bool,
+
+    /// Starting frequency of the Swept-Sine in Hz
+    #[arg(
+        long,
+        default_value_t = super::LOW_FREQ_TSP_DEF.to_string(),
+    )]
+    pub startf: String,
+
+    /// Ending frequency of the Swept-Sine in Hz
+    #[arg(
+        long,
+        default_value_t = super::HIGH_FREQ_TSP_DEF.to_string(),
+    )]
+    pub endf: String,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    /// order of the MLS
+    #[arg(
+        long,
+        default_value_t = super::MLS_ORDER_DEF,
+        value_parser = clap::value_parser!(u32).range(2..=24),
+    )]
+    pub order: u32,
+
+    /// feedback taps of the MLS shift register including the order (e.g. 16,15,13,4)
+    #[arg(long)]
+    pub taps: Option<String>,
+
+    /// seed of the MLS used by `gen mls`
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// number of repeated periods in the recording for synchronous averaging
+    #[arg(
+        short, long,
+        default_value_t = 1,
+    )]
+    pub periods: usize,
+
+    /// number of harmonic distortion IRs to separate (log_sweep and sync_sweep only).
+    /// each IR is written to <OUTPUT>_h<N>.wav
+    #[arg(
+        long,
+        default_value_t = 0,
+    )]
+    pub harmonics: usize,
+
+    /// length of the output IR (e.g. 500msec)
+    #[arg(long)]
+    pub ir_length: Option<String>,
+
+    /// normalize the IR peak
+    #[arg(short, long)]
+    pub normalize: bool,
+
+    ///  Output filename.
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    #[command(flatten)]
+    pub taper_opt: common::TaperSpecOptions,
+}
@@ -1,7 +1,51 @@
 use clap::Args;
+use super::common;
 
 #[derive(Args, Debug, Clone)]
 pub struct ModOptions {
-    #[arg(required = true)]
-    inputs: Vec<String>,
+    /// input filename (carrier)
+    pub input: String,
+
+    /// type of modulation
+    #[arg(
+        short, long,
+        default_value = "am",
+        value_parser = ["am", "fm", "pm", "ring"],
+    )]
+    pub type_of_modulation: String,
+
+    /// modulator wav file, mono or the same channels as the input, at least as long as the input.
+    /// If omitted, a sine wave is generated as the modulator.
+    #[arg(short, long)]
+    pub modulator: Option<String>,
+
+    /// Frequency of the sine wave modulator in Hz.
+    /// For fm, the frequency deviation is index * frequency.
+    #[arg(
+        short, long,
+        default_value_t = super::MOD_FREQ_DEF.to_string(),
+    )]
+    pub frequency: String,
+
+    #[command(flatten)]
+    pub tuning: common::TuningOptions,
+
+    /// modulation depth of am (0.0 - 1.0)
+    #[arg(
+        short, long,
+        default_value_t = super::MOD_DEPTH_DEF,
+    )]
+    pub depth: f64,
+
+    /// modulation index of fm and pm
+    #[arg(
+        short, long,
+        default_value_t = super::MOD_INDEX_DEF,
+    )]
+    pub index: f64,
+
+    ///  Output filename.
+    /// If specified without an argument, input file will be overridden.
+    #[arg(short, long)]
+    pub output: Option<Option<String>>,
 }
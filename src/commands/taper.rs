@@ -10,6 +10,14 @@ pub struct TaperOptions {
     #[arg(short, long)]
     pub output: Option<Option<String>>,
 
+    /// Bits per sample of the output (32f: 32-bit float).
+    /// If omitted, the format of the input file is preserved.
+    #[arg(
+        short, long,
+        value_parser = ["16", "24", "32", "32f"],
+    )]
+    pub bits: Option<String>,
+
     #[command(flatten)]
     pub taper_opt: super::common::TaperSpecOptions,
 }
@@ -1,7 +1,8 @@
 use std::path::Path;
-use std::io::{self, Write};
+use std::fs::OpenOptions;
+use std::io::{self, Seek, SeekFrom, Write};
 
-use hound::{WavSpec, WavReader, WavWriter};
+use hound::{SampleFormat, WavSpec, WavReader, WavWriter};
 
 const RIFF_HEADER_SIZE: usize = 44; // bytes
 const FILESIZE_WARN_LEVEL: usize = 1_000_000_000; // 1GB
@@ -80,11 +81,15 @@ fn output_filesize_check(filesize: usize) -> Result<(), Box<dyn std::error::Erro
     }
 }
 
-fn freq_format(freq: i32, prefix: &str) -> String {
+// the note name is used instead of the frequency if given
+pub fn freq_format(freq: i32, note: Option<&str>, prefix: &str) -> String {
+    if let Some(note) = note {
+        return format!("_{}{}", prefix, note);
+    }
     if freq < 0 {
         return String::new();
     }
-    
+
     if freq < 1000 {
         format!("_{}{}hz", prefix, freq)
     } else {
@@ -119,18 +124,29 @@ fn extract_stem(input_filename: &str) -> String {
         .unwrap_or("").to_string()
 }
 
+// e.g. ir.wav and "h2" -> ir_h2.wav
+pub fn suffixed_filename(filename: &str, suffix: &str) -> String {
+    let path = Path::new(filename);
+    let name = format!("{}_{}.wav", extract_stem(filename), suffix);
+    match path.parent() {
+        Some(dir) => dir.join(name).to_string_lossy().to_string(),
+        None => name,
+    }
+}
+
 pub fn gen_file_name(
     output_filename: &Option<String>,
     sig_type: String,
     start_freq: i32,
     end_freq: i32,
+    notes: (Option<String>, Option<String>),
     filename_ch: &str,
-    d_cmd: &str) -> Result<FileInfo, Box<dyn std::error::Error>> {
+    d_cmd: &str) -> Result<String, Box<dyn std::error::Error>> {
     let filename = if let Some(name) = output_filename {
         name.clone()
     }else {
-        let filename_start_freq = freq_format(start_freq, "");
-        let filename_end_freq = freq_format(end_freq, "to_");
+        let filename_start_freq = freq_format(start_freq, notes.0.as_deref(), "");
+        let filename_end_freq = freq_format(end_freq, notes.1.as_deref(), "to_");
         let filename_duration = duration_format(d_cmd);
 
         format!(
@@ -141,19 +157,24 @@ pub fn gen_file_name(
 
     validate_wav_file(&filename)?;
 
-    let mut override_msg = String::new();
-    if is_file_exist(&filename) {
-        file_override_check(&filename)?;
-        override_msg = "(file override)".to_string();
+    Ok(filename)
+}
+
+// asks before overwriting an existing file, returns the message of the override
+pub fn output_file_check(filename: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if is_file_exist(filename) {
+        file_override_check(filename)?;
+        return Ok("(file override)".to_string());
     }
 
-    Ok(FileInfo {
-        name: filename,
-        exists_msg: override_msg,
-    })
+    Ok(String::new())
 }
 
-pub fn set_output_filename(output_filename: Option<Option<String>>, input_filename: &str) -> Result<FileInfo, Box<dyn std::error::Error>> {
+pub fn set_output_filename(
+    output_filename: Option<Option<String>>,
+    input_filename: &str,
+    suffix: &str,
+) -> Result<FileInfo, Box<dyn std::error::Error>> {
     let mut fileinfo = FileInfo{
         name: String::new(),
         exists_msg: String::new(),
@@ -168,7 +189,7 @@ pub fn set_output_filename(output_filename: Option<Option<String>>, input_filena
             enable_file_exists_check = false;
             input_filename.to_string() // input file will override
         }
-        None => format!("{}_tapered.wav", extract_stem(input_filename)), // use default name
+        None => format!("{}_{}.wav", extract_stem(input_filename), suffix), // use default name
     };
 
     validate_wav_file(filename.as_str())?;
@@ -187,18 +208,48 @@ pub fn set_output_filename(output_filename: Option<Option<String>>, input_filena
     Ok(fileinfo)
 }
 
+pub fn parse_sample_format(bits: &str) -> Result<(u16, SampleFormat), Box<dyn std::error::Error>> {
+    match bits {
+        "16" => Ok((16, SampleFormat::Int)),
+        "24" => Ok((24, SampleFormat::Int)),
+        "32" => Ok((32, SampleFormat::Int)),
+        "32f" => Ok((32, SampleFormat::Float)),
+        _ => Err(format!("unsupported sample format [{}]", bits).into()),
+    }
+}
+
+// the value of 1.0 in integer samples
+fn full_scale(spec: &WavSpec) -> f64 {
+    match spec.sample_format {
+        SampleFormat::Int => ((1_i64 << (spec.bits_per_sample - 1)) - 1) as f64,
+        SampleFormat::Float => 1.0,
+    }
+}
+
 pub fn read_wav_file(filename: &str) -> Result<(Vec<Vec<f64>>, WavSpec), Box<dyn std::error::Error>> {
     validate_wav_file(filename)?;
 
     let mut reader =  WavReader::open(filename)?;
-    let spec = reader.spec().clone();
+    let spec = reader.spec();
     let num_channels = spec.channels as usize;
     let mut samples = vec![Vec::new(); num_channels];
-
-    for (i, sample) in reader.samples::<i16>().enumerate() {
-        let sample = sample?;
-        let channel = i % num_channels;
-        samples[channel].push(sample as f64 / i16::MAX as f64);
+    let scale = full_scale(&spec);
+
+    match spec.sample_format {
+        SampleFormat::Int => {
+            for (i, sample) in reader.samples::<i32>().enumerate() {
+                let sample = sample?;
+                let channel = i % num_channels;
+                samples[channel].push(sample as f64 / scale);
+            }
+        }
+        SampleFormat::Float => {
+            for (i, sample) in reader.samples::<f32>().enumerate() {
+                let sample = sample?;
+                let channel = i % num_channels;
+                samples[channel].push(sample as f64);
+            }
+        }
     }
 
     Ok((samples, spec))
@@ -209,8 +260,6 @@ pub fn write_wav_file(
     spec: WavSpec,
     filename: &str,
     samples: &[Vec<f64>],
-    enable_l: bool,
-    enable_r: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     validate_wav_file(filename)?;
 
@@ -218,24 +267,121 @@ pub fn write_wav_file(
 
     let samples_per_ch = samples[0].len();
     let num_ch = samples.len();
+    let bytes_per_sample = (spec.bits_per_sample as usize).div_ceil(8);
+    let scale = full_scale(&spec);
 
-    let output_filesize = (samples_per_ch * num_ch) * std::mem::size_of::<i16>() + RIFF_HEADER_SIZE;
+    let output_filesize = (samples_per_ch * num_ch) * bytes_per_sample + RIFF_HEADER_SIZE;
     output_filesize_check(output_filesize)?;
 
     for i in 0..samples_per_ch {
         for j in 0 .. num_ch {
-            let enabled = match j {
-                0 => enable_l,
-                1 => enable_r,
-                _ => true,
-            };
-
-            let sample_value: i16 =
-            (samples[j][i] * i16::MAX as f64).clamp(i16::MIN as f64, i16::MAX as f64) as i16;
-            writer.write_sample(if enabled { sample_value } else { 0 })?;
+            let value = samples[j][i];
+
+            match spec.sample_format {
+                SampleFormat::Int => {
+                    let sample_value: i32 = (value * scale).clamp(-scale - 1.0, scale) as i32;
+                    writer.write_sample(sample_value)?;
+                }
+                SampleFormat::Float => {
+                    writer.write_sample(value as f32)?;
+                }
+            }
         }
     }
 
     writer.flush()?;
     Ok(())
 }
+
+// overwrite dwChannelMask of WAVE_FORMAT_EXTENSIBLE, hound always sets the first N speakers
+pub fn write_channel_mask(filename: &str, mask: u32) -> Result<(), Box<dyn std::error::Error>> {
+    const FORMAT_TAG_OFFSET: u64 = 20;
+    const CHANNEL_MASK_OFFSET: u64 = 40;
+    const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+    let mut file = OpenOptions::new().read(true).write(true).open(filename)?;
+
+    let mut format_tag = [0u8; 2];
+    file.seek(SeekFrom::Start(FORMAT_TAG_OFFSET))?;
+    io::Read::read_exact(&mut file, &mut format_tag)?;
+    if u16::from_le_bytes(format_tag) != WAVE_FORMAT_EXTENSIBLE {
+        return Ok(());
+    }
+
+    file.seek(SeekFrom::Start(CHANNEL_MASK_OFFSET))?;
+    file.write_all(&mask.to_le_bytes())?;
+    file.flush()?;
+
+    Ok(())
+}
+
+// dwChannelMask of WAVE_FORMAT_EXTENSIBLE, None for the other formats
+pub fn read_channel_mask(filename: &str) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+    const RIFF_HEADER_LEN: u64 = 12;
+    const FMT_EXTENSIBLE_LEN: u32 = 40;
+    const FMT_CHANNEL_MASK_OFFSET: usize = 20;
+    const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+    let mut file = std::fs::File::open(filename)?;
+    let file_len = file.metadata()?.len();
+    let mut pos = RIFF_HEADER_LEN;
+
+    while pos + 8 <= file_len {
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(pos))?;
+        io::Read::read_exact(&mut file, &mut header)?;
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+        if &header[0..4] == b"fmt " {
+            if size < FMT_EXTENSIBLE_LEN {
+                return Ok(None);
+            }
+            let mut fmt = [0u8; FMT_EXTENSIBLE_LEN as usize];
+            io::Read::read_exact(&mut file, &mut fmt)?;
+            if u16::from_le_bytes([fmt[0], fmt[1]]) != WAVE_FORMAT_EXTENSIBLE {
+                return Ok(None);
+            }
+            let mask = &fmt[FMT_CHANNEL_MASK_OFFSET..FMT_CHANNEL_MASK_OFFSET + 4];
+            return Ok(Some(u32::from_le_bytes([mask[0], mask[1], mask[2], mask[3]])));
+        }
+
+        pos += 8 + size as u64 + (size % 2) as u64; // chunks are word aligned
+    }
+
+    Ok(None)
+}
+
+// append LIST-INFO chunk with ICMT (comment) to the end of the file
+pub fn write_info_comment(filename: &str, comment: &str) -> Result<(), Box<dyn std::error::Error>> {
+    const RIFF_SIZE_OFFSET: u64 = 4;
+
+    let mut text = comment.as_bytes().to_vec();
+    text.push(0);
+    let text_len = text.len() as u32;
+    if text.len() % 2 == 1 {
+        text.push(0);
+    }
+
+    let mut chunk = Vec::new();
+    chunk.extend_from_slice(b"LIST");
+    chunk.extend_from_slice(&(4 + 8 + text.len() as u32).to_le_bytes());
+    chunk.extend_from_slice(b"INFO");
+    chunk.extend_from_slice(b"ICMT");
+    chunk.extend_from_slice(&text_len.to_le_bytes());
+    chunk.extend_from_slice(&text);
+
+    let mut file = OpenOptions::new().read(true).write(true).open(filename)?;
+    let mut len = file.seek(SeekFrom::End(0))?;
+    if len % 2 == 1 {
+        file.write_all(&[0])?; // pad byte of the data chunk
+        len += 1;
+    }
+    file.write_all(&chunk)?;
+
+    let riff_size = (len + chunk.len() as u64 - 8) as u32;
+    file.seek(SeekFrom::Start(RIFF_SIZE_OFFSET))?;
+    file.write_all(&riff_size.to_le_bytes())?;
+    file.flush()?;
+
+    Ok(())
+}
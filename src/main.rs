@@ -11,5 +11,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         commands::Commands::Wav(opt) => processing::cat_wav_files(&opt),
         commands::Commands::Mod(opt) => processing::wav_modurate(&opt),
         commands::Commands::Conv(opt) => processing::wav_conv(&opt),
+        commands::Commands::Ir(opt) => processing::measure_ir(&opt),
     }
 }
@@ -5,9 +5,10 @@ use crate::fileio;
 
 pub mod gen;
 mod cat;
-
-const CH: u16 = 2; // stereo
-pub const BITS_PER_SAMPLE: u16 = 16;
+mod channel;
+mod modurate;
+mod conv;
+mod ir;
 
 pub fn value_verify<T>(value: T, min: T, max: T) -> T
 where
@@ -28,91 +29,349 @@ where
 
 pub fn apply_taper_to_wav(options: &commands::taper::TaperOptions) -> Result<(), Box<dyn std::error::Error>> {
     let taper_spec = gen::get_taper_spec(Some(&options.taper_opt)).unwrap();
-    let (mut samples, spec) = fileio::read_wav_file(options.input.as_str())?;
+    let (mut samples, mut spec) = fileio::read_wav_file(options.input.as_str())?;
+    let mask = fileio::read_channel_mask(options.input.as_str())?;
     let num_ch = samples.len();
 
+    if let Some(bits) = &options.bits {
+        (spec.bits_per_sample, spec.sample_format) = fileio::parse_sample_format(bits)?;
+    }
+
     for i in 0..num_ch {
         rtaper::apply_taper_both(&mut samples[i], &taper_spec)?;
     }
 
-    let fileinfo = crate::fileio::set_output_filename(options.output.clone(), options.input.as_str())?;
-    fileio::write_wav_file(spec, fileinfo.name.as_str(), &samples, true, true)?;
+    let fileinfo = crate::fileio::set_output_filename(options.output.clone(), options.input.as_str(), "tapered")?;
+    fileio::write_wav_file(spec, fileinfo.name.as_str(), &samples)?;
+    restore_channel_mask(fileinfo.name.as_str(), mask)?;
 
     println!("WAV file [{}] created successfully {}", fileinfo.name, fileinfo.exists_msg);
 
     Ok(())
 }
 
+// hound writes the mask of the first N speakers, the mask of the input file is written back
+fn restore_channel_mask(filename: &str, mask: Option<u32>) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(mask) = mask {
+        fileio::write_channel_mask(filename, mask)?;
+    }
+
+    Ok(())
+}
+
+// the seed of a random signal is appended to info, so that the file can be regenerated
+fn resolve_seed(seed: Option<u64>, info: &mut Vec<String>) -> u64 {
+    let seed = seed.unwrap_or_else(rand::random);
+    println!("seed: {}", seed);
+    info.push(format!("seed={}", seed));
+    seed
+}
+
+fn generate_waveform(
+    waveform: &WaveFormCommands,
+    signal_spec: &gen::SignalSpec,
+    startf: f64,
+    endf: f64,
+    info: &mut Vec<String>,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let mut samples = match waveform {
+        WaveFormCommands::Sine(_) => {
+            gen::generate_sine_wave(signal_spec, startf)
+        }
+        WaveFormCommands::Noise(noise_options) => {
+            let crest_factor = match &noise_options.crest_factor {
+                Some(cmd) => Some(gen::parse_crest_factor(cmd)?),
+                None => None,
+            };
+            let seed = resolve_seed(noise_options.seed, info);
+            gen::generate_noise(
+                signal_spec,
+                &noise_options.noise_type,
+                &noise_options.distribution,
+                crest_factor,
+                noise_options.get_band(signal_spec.fs)?,
+                seed,
+            )
+        }
+        WaveFormCommands::Tsp(tsp_options) => {
+            gen::generate_tsp_signal(
+                signal_spec,
+                &tsp_options.tsp_type,
+                tsp_options.flip,
+                tsp_options.get_band(signal_spec.fs)?,
+                if tsp_options.is_periodic() { Some(tsp_options.get_length()?) } else { None },
+                tsp_options.repeat,
+            )
+        }
+        WaveFormCommands::Sweep(sweep_options) => {
+            let mut sweep = gen::generate_faded_sweep(signal_spec, sweep_options, startf, endf)?;
+            // the rms is of the sweep itself, not of the silence around it
+            if let Some(rms) = signal_spec.rms {
+                gen::scale_to_rms(&mut sweep, rms);
+            }
+            gen::pad_silence(sweep, &sweep_options.pre_silence, &sweep_options.post_silence, signal_spec.fs)
+        }
+        WaveFormCommands::Pwm(pwm_options) => {
+            let d_verified = value_verify(pwm_options.percent_of_duty, 0, 100) as f64;
+            gen::generate_pwm_signal(signal_spec, startf, d_verified)
+        }
+        WaveFormCommands::Square(square_options) => {
+            let duty = value_verify(square_options.percent_of_duty, 0.0, 100.0) / 100.0;
+            gen::generate_periodic_wave(signal_spec, "square", startf, duty, &square_options.method)
+        }
+        WaveFormCommands::Triangle(triangle_options) => {
+            let skew = value_verify(triangle_options.skew, 0.0, 100.0) / 100.0;
+            gen::generate_periodic_wave(signal_spec, "triangle", startf, skew, &triangle_options.method)
+        }
+        WaveFormCommands::Sawtooth(sawtooth_options) => {
+            gen::generate_periodic_wave(signal_spec, "sawtooth", startf, 0.0, &sawtooth_options.method)
+        }
+        WaveFormCommands::Multitone(multitone_options) => {
+            let freqs = multitone_options.get_frequencies(signal_spec.fs)?;
+            let seed = if multitone_options.type_of_phase == "random" {
+                resolve_seed(multitone_options.seed, info)
+            }else {
+                0
+            };
+            let phases = gen::multitone_phases(&freqs, &multitone_options.type_of_phase, seed)?;
+            gen::generate_multitone(signal_spec, &freqs, &phases, multitone_options.fft_size.is_some())
+        }
+        WaveFormCommands::Stepped(stepped_options) => {
+            let freqs = stepped_options.get_frequencies(signal_spec.fs)?;
+            let dwells = stepped_options.get_dwells(freqs.len())?;
+            let gap = gen::parse_duration(&stepped_options.gap)?;
+            gen::generate_stepped_sine(signal_spec, &freqs, &dwells, gap)
+        }
+        WaveFormCommands::Mls(mls_options) => {
+            let taps = gen::mls_taps(mls_options.order, &mls_options.taps)?;
+            let seed = resolve_seed(mls_options.seed, info);
+            gen::generate_mls(signal_spec, mls_options.order, &taps, seed, mls_options.periods)
+        }
+        WaveFormCommands::Golay(golay_options) => {
+            let gap = if golay_options.separate { 0.0 } else { gen::parse_duration(&golay_options.gap)? };
+            gen::generate_golay_pair(signal_spec, golay_options.length, gap)
+        }
+        WaveFormCommands::Impulse(impulse_options) => {
+            let position = gen::parse_position(&impulse_options.position, signal_spec.fs)?;
+            let sign = gen::polarity_sign(&impulse_options.polarity);
+            gen::generate_impulse(signal_spec, position, impulse_options.width, sign)
+        }
+        WaveFormCommands::Step(step_options) => {
+            let position = gen::parse_position(&step_options.position, signal_spec.fs)?;
+            let sign = gen::polarity_sign(&step_options.polarity);
+            gen::generate_step(signal_spec, position, sign)
+        }
+        WaveFormCommands::Clicks(clicks_options) => {
+            let rate = gen::parse_rate(&clicks_options.rate)?;
+            let sign = gen::polarity_sign(&clicks_options.polarity);
+            gen::generate_clicks(signal_spec, rate, clicks_options.width, sign)
+        }
+        WaveFormCommands::Dtmf(dtmf_options) => {
+            match &dtmf_options.preset {
+                Some(preset) => gen::generate_call_progress_tone(signal_spec, preset, &dtmf_options.region),
+                None => {
+                    let tone = gen::parse_duration(&dtmf_options.tone)?;
+                    let pause = gen::parse_duration(&dtmf_options.pause)?;
+                    gen::generate_dtmf(signal_spec, dtmf_options.digits.as_deref().unwrap_or(""), tone, pause)
+                }
+            }
+        }
+        WaveFormCommands::Imd(imd_options) => {
+            let (f1, f2, ratio) = imd_options.get_tones()?;
+            gen::generate_imd(signal_spec, &imd_options.preset, f1, f2, ratio)
+        }
+        WaveFormCommands::Burst(burst_options) => {
+            let freq = gen::parse_tuned_freq(&burst_options.frequency, &burst_options.tuning)?;
+            let period = match &burst_options.period {
+                Some(cmd) => Some(gen::parse_duration(cmd)?),
+                None => None,
+            };
+            gen::generate_burst(signal_spec, freq, burst_options.get_cycles(), burst_options.get_window(), period)
+        }
+        WaveFormCommands::Chord(chord_options) => {
+            gen::generate_chord(signal_spec, &chord_options.get_frequencies()?)
+        }
+        WaveFormCommands::Zeros(_) => {
+            gen::generate_zeros(signal_spec)
+        }
+        WaveFormCommands::Multi(_) => {
+            Err("multi cannot be generated as a single channel signal".into())
+        }
+    }?;
+
+    if let (Some(rms), false) = (signal_spec.rms, matches!(waveform, WaveFormCommands::Sweep(_))) {
+        gen::scale_to_rms(&mut samples, rms);
+    }
+
+    Ok(samples)
+}
+
+// each signal uses its own amplitude and parameters, duration and sample rate are shared
+fn generate_multi_channel(
+    options: &commands::gen::MultiOptions,
+    signal_spec: &gen::SignalSpec,
+    layout: &channel::ChannelLayout,
+    info: &mut Vec<String>,
+) -> Result<Vec<Vec<f64>>, Box<dyn std::error::Error>> {
+    let mut output: Vec<Vec<f64>> = vec![Vec::new(); layout.num_channels()];
+
+    for (channels, waveform) in options.parse_commands()? {
+        let (enabled, _) = channel::parse_channels(&channels, layout)?;
+
+        let mut spec = waveform.get_common_opt().get_signal_spec(waveform.get_taper_spec(), signal_spec.d)?;
+        spec.fs = signal_spec.fs;
+        let (_, startf, endf) = waveform.get_fileinfo(spec.fs)?;
+        let mut channel_info = Vec::new();
+        let samples = generate_waveform(&waveform, &spec, startf, endf, &mut channel_info)?;
+        info.extend(channel_info.iter().map(|i| format!("{}:{}", channels, i)));
+
+        for (ch, _) in enabled.iter().enumerate().filter(|(_, &e)| e) {
+            if !output[ch].is_empty() {
+                return Err(format!("channel [{}] is specified more than once", layout.names[ch]).into());
+            }
+            output[ch] = samples.clone();
+        }
+    }
+
+    // signals of different lengths (e.g. TSP) are padded with zeros
+    let len = output.iter().map(|ch| ch.len()).max().unwrap_or(0);
+    for ch in output.iter_mut() {
+        ch.resize(len, 0.0);
+    }
+
+    Ok(output)
+}
+
 pub fn signal_generator(args: &commands::gen::GenOptions) -> Result<(), Box<dyn std::error::Error>> {
     let common_options = args.waveform.get_common_opt();
     let (d, d_cmd) = if let Some(cmd) = &common_options.size_of_file {
         let tmp = args.waveform.get_duration_from_filesize(&cmd)?;
-        (tmp, cmd)
+        (tmp, cmd.clone())
     }else {
         let tmp = args.waveform.get_duration_in_sec()?;
-        (tmp, args.waveform.get_duration_cmd())
+        (tmp, args.waveform.get_duration_cmd()?)
     };
     println!("duration: {:?}", d);
     let taper_spec = args.waveform.get_taper_spec();
-    let signal_spec = common_options.get_signal_spec(taper_spec, d);
+    let signal_spec = common_options.get_signal_spec(taper_spec, d)?;
 
-    let (enable_l, enable_r, filename_ch) = match signal_spec.ch.as_str() {
-        "L" => (true, false, "_l_only"),
-        "R" => (false, true, "_r_only"),
-        "LR" => (true, true, ""),
-        _ => {
-            return Err("unknown spec ch std::error::error".into());
-        }
-    };
+    let layout = channel::parse_layout(&common_options.layout)?;
+    let (enabled, filename_ch) = channel::parse_channels(&signal_spec.ch, &layout)?;
 
-    let (sig_type, startf, endf) = args.waveform.get_fileinfo(signal_spec.fs);
+    let (sig_type, startf, endf) = args.waveform.get_fileinfo(signal_spec.fs)?;
 
-    let fileinfo = fileio::gen_file_name(
+    // multi is as long as the longest signal (e.g. TSP), it is generated before the filename is given
+    let mut info = Vec::new();
+    let multi_samples = match &args.waveform {
+        WaveFormCommands::Multi(multi_options) => Some(generate_multi_channel(multi_options, &signal_spec, &layout, &mut info)?),
+        _ => None,
+    };
+    let d_cmd = match multi_samples.as_ref().and_then(|s| s.first()) {
+        Some(ch) if ch.len() != (d * signal_spec.fs) as usize => (ch.len() as f64 / signal_spec.fs).to_string(),
+        _ => d_cmd,
+    };
+
+    let filename = fileio::gen_file_name(
         &common_options.output_filename,
         sig_type,
         startf as i32,
         endf as i32,
-        filename_ch,
-        d_cmd,
+        args.waveform.get_note_labels(),
+        &filename_ch,
+        &d_cmd,
     )?;
 
+    // the sequence A and B of a Golay pair are written to the separate files
+    let filenames = match &args.waveform {
+        WaveFormCommands::Golay(golay_options) if golay_options.separate => vec![
+            fileio::suffixed_filename(&filename, "a"),
+            fileio::suffixed_filename(&filename, "b"),
+        ],
+        _ => vec![filename.clone()],
+    };
+    // the inverse filter of a sweep is written as a mono file
+    let inverse = matches!(&args.waveform, WaveFormCommands::Sweep(opt) if opt.inverse);
+    let filenames = if inverse {
+        [filenames, vec![fileio::suffixed_filename(&filename, "inverse")]].concat()
+    }else {
+        filenames
+    };
+    let override_msgs = filenames.iter()
+        .map(|f| fileio::output_file_check(f))
+        .collect::<Result<Vec<String>, _>>()?;
+
     // generate signals
-    let samples;
-    match &args.waveform {
-        WaveFormCommands::Sine(_) => {
-            samples = gen::generate_sine_wave(&signal_spec, startf)?;
-        }
-        WaveFormCommands::Noise(noise_options) => {
-            samples = gen::generate_noise(&signal_spec, &noise_options.noise_type)?;
-        }
-        WaveFormCommands::Tsp(tsp_options) => {
-            samples = gen::generate_tsp_signal(&signal_spec, &tsp_options.tsp_type, tsp_options.flip)?;
-        }
-        WaveFormCommands::Sweep(sweep_options) => {
-            samples = gen::generate_sweep_signal(&signal_spec, &sweep_options.type_of_sweep, startf, endf)?;
+    let samples_to_write = match multi_samples {
+        Some(samples) => samples,
+        None => {
+            let samples = generate_waveform(&args.waveform, &signal_spec, startf, endf, &mut info)?;
+            enabled.iter()
+                .map(|&e| if e { samples.clone() } else { vec![0.0; samples.len()] })
+                .collect()
         }
-        WaveFormCommands::Pwm(pwm_options) => {
-            let d_verified = value_verify(pwm_options.percent_of_duty, 0, 100) as f64;
-            samples = gen::generate_pwm_signal(&signal_spec, startf, d_verified)?;
-        }
-        WaveFormCommands::Zeros(_) => {
-            samples = gen::generate_zeros(&signal_spec)?;
+    };
+
+    let mut outputs = match &args.waveform {
+        WaveFormCommands::Golay(golay_options) if golay_options.separate => {
+            let (a, b): (Vec<Vec<f64>>, Vec<Vec<f64>>) = samples_to_write.into_iter()
+                .map(|mut ch| {
+                    let b = ch.split_off(golay_options.length.min(ch.len()));
+                    (ch, b)
+                })
+                .unzip();
+            vec![a, b]
         }
+        _ => vec![samples_to_write],
+    };
+
+    // the channel controls are applied after the split, a delay is kept in each file
+    for samples in outputs.iter_mut() {
+        channel::apply_channel_controls(samples, common_options, &layout, signal_spec.fs)?;
     }
 
     // write wav file
+    let (bits_per_sample, sample_format) = fileio::parse_sample_format(&common_options.bits)?;
     let wav_spec = WavSpec {
-        channels: CH,
+        channels: layout.num_channels() as u16,
         sample_rate: signal_spec.fs as u32,
-        bits_per_sample: BITS_PER_SAMPLE,
-        sample_format: hound::SampleFormat::Int,
+        bits_per_sample,
+        sample_format,
     };
 
-    let samples_to_write = [samples.clone(), samples];
+    let mut outputs: Vec<(Vec<Vec<f64>>, Vec<String>)> = outputs.into_iter()
+        .map(|samples| (samples, info.clone()))
+        .collect();
+
+    // the inverse filter is normalized to the peak of 1.0 not to lose the resolution,
+    // the gain to restore the unity gain at the center of the band is recorded
+    if let WaveFormCommands::Sweep(sweep_options) = &args.waveform {
+        if sweep_options.inverse {
+            let sweep = gen::generate_faded_sweep(&signal_spec, sweep_options, startf, endf)?;
+            let mut inverse = gen::sweep_inverse_filter(&sweep, &sweep_options.type_of_sweep, startf, endf, signal_spec.fs);
+            let peak = inverse.iter().map(|v| v.abs()).fold(0.0, f64::max);
+            if peak > 0.0 {
+                for v in inverse.iter_mut() {
+                    *v /= peak;
+                }
+            }
+            println!("inverse filter gain: {:e}", peak);
+            let inverse_info = [info.clone(), vec![format!("inverse_gain={:e}", peak)]].concat();
+            outputs.push((vec![inverse], inverse_info));
+        }
+    }
 
-    fileio::write_wav_file(wav_spec, &fileinfo.name, &samples_to_write, enable_l, enable_r)?;
+    for ((filename, override_msg), (samples, info)) in filenames.iter().zip(&override_msgs).zip(&outputs) {
+        let wav_spec = WavSpec { channels: samples.len() as u16, ..wav_spec };
+        fileio::write_wav_file(wav_spec, filename, samples)?;
+        if samples.len() == layout.num_channels() {
+            fileio::write_channel_mask(filename, layout.mask)?;
+        }
+        if !info.is_empty() {
+            fileio::write_info_comment(filename, &info.join(" "))?;
+        }
 
-    println!("WAV file [{}] created successfully {}", fileinfo.name, fileinfo.exists_msg);
+        println!("WAV file [{}] created successfully {}", filename, override_msg);
+    }
 
     Ok(())
 }
@@ -120,7 +379,8 @@ pub fn signal_generator(args: &commands::gen::GenOptions) -> Result<(), Box<dyn
 pub fn cat_wav_files(options: &commands::wav::WavOptions) -> Result<(), Box<dyn std::error::Error>> {
     let (inputs, cat_cmd, output_filename) = options.parse_commands()?;
     let input_files: indexmap::IndexMap<String, String> = cat::parse_input_files(&inputs)?;
-    let (spec, samples) = cat::parse_cat_commands(input_files, cat_cmd)?;
+    let mut samples: Vec<Vec<f64>> = Vec::new();
+    let (spec, mask) = cat::parse_cat_commands(input_files, cat_cmd, &mut samples)?;
 
     let mut override_msg = String::new();
     if fileio::is_file_exist(output_filename.as_str()) {
@@ -128,17 +388,71 @@ pub fn cat_wav_files(options: &commands::wav::WavOptions) -> Result<(), Box<dyn
         override_msg = "(file override)".to_string();
     }
 
-    fileio::write_wav_file(spec, output_filename.as_str(), &samples, true, true)?;
+    fileio::write_wav_file(spec, output_filename.as_str(), &samples)?;
+    restore_channel_mask(output_filename.as_str(), mask)?;
 
     println!("WAV file [{}] created successfully {}", output_filename, override_msg);
 
     Ok(())
 }
 
-pub fn wav_modurate(_options: &commands::modurate::ModOptions) -> Result<(), Box<dyn std::error::Error>> {
+pub fn wav_modurate(options: &commands::modurate::ModOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let (carrier, spec) = fileio::read_wav_file(options.input.as_str())?;
+    let mask = fileio::read_channel_mask(options.input.as_str())?;
+    let samples = modurate::modurate(options, &carrier, &spec)?;
+
+    let fileinfo = fileio::set_output_filename(
+        options.output.clone(),
+        options.input.as_str(),
+        options.type_of_modulation.as_str(),
+    )?;
+    fileio::write_wav_file(spec, fileinfo.name.as_str(), &samples)?;
+    restore_channel_mask(fileinfo.name.as_str(), mask)?;
+
+    println!("WAV file [{}] created successfully {}", fileinfo.name, fileinfo.exists_msg);
+
     Ok(())
 }
 
-pub fn wav_conv(_options: &commands::conv::ConvOptions) -> Result<(), Box<dyn std::error::Error>> {
+pub fn wav_conv(options: &commands::conv::ConvOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let (input, spec) = fileio::read_wav_file(options.input.as_str())?;
+    let (samples, out_spec) = conv::convolve(options, &input, &spec)?;
+    // a mono input takes the channels of the IR
+    let mask = if out_spec.channels == spec.channels {
+        fileio::read_channel_mask(options.input.as_str())?
+    }else {
+        fileio::read_channel_mask(options.ir.as_str())?
+    };
+
+    let fileinfo = fileio::set_output_filename(options.output.clone(), options.input.as_str(), "conv")?;
+    fileio::write_wav_file(out_spec, fileinfo.name.as_str(), &samples)?;
+    restore_channel_mask(fileinfo.name.as_str(), mask)?;
+
+    println!("WAV file [{}] created successfully {}", fileinfo.name, fileinfo.exists_msg);
+
     Ok(())
 }
+
+
+pub fn measure_ir(options: &commands::ir::IrOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let (recording, spec) = fileio::read_wav_file(options.recording.as_str())?;
+    let mask = fileio::read_channel_mask(options.recording.as_str())?;
+    let (linear, harmonics) = ir::measure_ir(options, &recording, &spec)?;
+
+    let fileinfo = fileio::set_output_filename(options.output.clone().map(Some), options.recording.as_str(), "ir")?;
+    fileio::write_wav_file(spec, fileinfo.name.as_str(), &linear)?;
+    restore_channel_mask(fileinfo.name.as_str(), mask)?;
+
+    println!("WAV file [{}] created successfully {}", fileinfo.name, fileinfo.exists_msg);
+
+    for (i, samples) in harmonics.iter().enumerate() {
+        let filename = fileio::suffixed_filename(fileinfo.name.as_str(), &format!("h{}", i + 2));
+        let override_msg = fileio::output_file_check(&filename)?;
+        fileio::write_wav_file(spec, filename.as_str(), samples)?;
+        restore_channel_mask(filename.as_str(), mask)?;
+
+        println!("WAV file [{}] created successfully {}", filename, override_msg);
+    }
+
+    Ok(())
+}
\ No newline at end of file
@@ -45,14 +45,16 @@ fn append_stereo_signal(input: &Vec<Vec<f64>>, target: &mut Vec<Vec<f64>>) {
 fn concatenate_no_interval(
     input_files: IndexMap<String, String>,
     samples: &mut Vec<Vec<f64>>,
-) -> Result<WavSpec, Box<dyn std::error::Error>> {
+) -> Result<(WavSpec, Option<u32>), Box<dyn std::error::Error>> {
     let mut spec:  Option<WavSpec> = None;
+    let mut mask: Option<u32> = None;
 
     for(_, filename) in input_files {
         let (read_buf, tmp_spec) = fileio::read_wav_file(filename.as_str())?;
 
         if spec.is_none() {
             spec = Some(tmp_spec);
+            mask = fileio::read_channel_mask(filename.as_str())?;
         }
 
         if samples.is_empty() {
@@ -62,7 +64,7 @@ fn concatenate_no_interval(
         }
     }
 
-    spec.ok_or_else(|| "spec is not set".into())
+    spec.map(|s| (s, mask)).ok_or_else(|| "spec is not set".into())
 }
 
 fn append_zeros(
@@ -87,8 +89,9 @@ fn do_cat_commands(
     cmd: Vec<String>,
     samples: &mut Vec<Vec<f64>>,
     filemap: IndexMap<String, String>,
-) -> Result<WavSpec, Box<dyn std::error::Error>>{
+) -> Result<(WavSpec, Option<u32>), Box<dyn std::error::Error>>{
     let mut spec: Option<WavSpec> = None;
+    let mut mask: Option<u32> = None;
     let flag = is_specify_key(&cmd);
 
     let mut i: usize = 0;
@@ -111,11 +114,12 @@ fn do_cat_commands(
             }
         };
 
-        if filename.is_some() {
-            let (read_buf, tmp_spec) = fileio::read_wav_file(filename.unwrap().as_str())?;
+        if let Some(filename) = filename {
+            let (read_buf, tmp_spec) = fileio::read_wav_file(filename.as_str())?;
 
             if spec.is_none() {
                 spec = Some(tmp_spec);
+                mask = fileio::read_channel_mask(filename.as_str())?;
             }
 
             if samples.is_empty() {
@@ -130,6 +134,7 @@ fn do_cat_commands(
                 let (_, tmp_filename) = filemap.get_index(0).unwrap();
                 let (_, tmp_spec) = fileio::read_wav_file(tmp_filename)?;
                 spec = Some(tmp_spec);
+                mask = fileio::read_channel_mask(tmp_filename)?;
             }
 
             if samples.is_empty() {
@@ -149,6 +154,7 @@ fn do_cat_commands(
 
         if spec.is_none() {
             spec = Some(tmp_spec);
+            mask = fileio::read_channel_mask(f)?;
         }
 
         append_stereo_signal(&read_buf, samples);
@@ -156,23 +162,20 @@ fn do_cat_commands(
         i += 1;
     }
 
-    spec.ok_or_else(|| "spec is not set".into())
+    spec.map(|s| (s, mask)).ok_or_else(|| "spec is not set".into())
 }
 
 pub fn parse_cat_commands(
     input_files:IndexMap<String, String>,
     cat_cmd: Option<Vec<String>>,
-) -> Result<(WavSpec, Vec<Vec<f64>>), Box<dyn std::error::Error>> {
-    let mut samples: Vec<Vec<f64>> = Vec::new();
-
-    let spec = match cat_cmd {
+    samples: &mut Vec<Vec<f64>>,
+) -> Result<(WavSpec, Option<u32>), Box<dyn std::error::Error>> {
+    match cat_cmd {
         Some(cat_commands_vec) => {
-            do_cat_commands(cat_commands_vec, &mut samples, input_files)?
+            do_cat_commands(cat_commands_vec, samples, input_files)
         }
         None => {
-            concatenate_no_interval(input_files, &mut samples)?
+            concatenate_no_interval(input_files, samples)
         }
-    };
-
-    Ok((spec, samples))
+    }
 }
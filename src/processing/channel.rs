@@ -0,0 +1,206 @@
+use crate::commands::common::CommonOptions;
+use crate::processing::{gen, modurate};
+
+// speaker positions of WAVE_FORMAT_EXTENSIBLE dwChannelMask
+const SPEAKERS: [(&str, u32); 18] = [
+    ("FL", 0x1),
+    ("FR", 0x2),
+    ("FC", 0x4),
+    ("LFE", 0x8),
+    ("BL", 0x10),
+    ("BR", 0x20),
+    ("FLC", 0x40),
+    ("FRC", 0x80),
+    ("BC", 0x100),
+    ("SL", 0x200),
+    ("SR", 0x400),
+    ("TC", 0x800),
+    ("TFL", 0x1000),
+    ("TFC", 0x2000),
+    ("TFR", 0x4000),
+    ("TBL", 0x8000),
+    ("TBC", 0x10000),
+    ("TBR", 0x20000),
+];
+
+const LAYOUTS: [(&str, u32); 10] = [
+    ("mono", 0x4),
+    ("stereo", 0x3),
+    ("2.1", 0xB),
+    ("quad", 0x33),
+    ("5.1", 0x3F),
+    ("7.1", 0x63F),
+    ("5.1.2", 0x503F),
+    ("5.1.4", 0x2D03F),
+    ("7.1.2", 0x563F),
+    ("7.1.4", 0x2D63F),
+];
+
+pub struct ChannelLayout {
+    pub name: String,
+    pub mask: u32,
+    pub names: Vec<String>,
+}
+
+impl ChannelLayout {
+    pub fn num_channels(&self) -> usize {
+        self.names.len()
+    }
+}
+
+// named layout (e.g. "5.1") or number of channels without speaker positions (e.g. "16")
+pub fn parse_layout(layout_cmd: &str) -> Result<ChannelLayout, Box<dyn std::error::Error>> {
+    if let Some((name, mask)) = LAYOUTS.iter().find(|(name, _)| name.eq_ignore_ascii_case(layout_cmd)) {
+        let names = SPEAKERS.iter()
+            .filter(|(_, bit)| mask & bit != 0)
+            .map(|(speaker, _)| speaker.to_string())
+            .collect();
+
+        return Ok(ChannelLayout { name: name.to_string(), mask: *mask, names });
+    }
+
+    match layout_cmd.parse::<u16>() {
+        Ok(n) if n > 0 => Ok(ChannelLayout {
+            name: format!("{}ch", n),
+            mask: 0,
+            names: (1..=n).map(|i| format!("CH{}", i)).collect(),
+        }),
+        _ => Err(format!("unknown channel layout [{}]", layout_cmd).into()),
+    }
+}
+
+fn find_channel(token: &str, layout: &ChannelLayout) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+    let n = layout.num_channels();
+    let speaker_index = |name: &str| layout.names.iter().position(|s| s.eq_ignore_ascii_case(name));
+
+    match token.to_uppercase().as_str() {
+        "ALL" => Ok((0..n).collect()),
+        "L" => Ok(vec![speaker_index("FL").unwrap_or(0)]),
+        "R" => Ok(vec![speaker_index("FR").unwrap_or(1)]),
+        "LR" => Ok(vec![speaker_index("FL").unwrap_or(0), speaker_index("FR").unwrap_or(1)]),
+        _ => {
+            if let Ok(i) = token.parse::<usize>() {
+                if (1..=n).contains(&i) {
+                    return Ok(vec![i - 1]);
+                }
+            }else if let Some(i) = speaker_index(token) {
+                return Ok(vec![i]);
+            }
+            Err(format!("channel [{}] is not in the layout [{}]", token, layout.name).into())
+        }
+    }
+}
+
+// returns which channels carry the signal and the suffix of the output filename
+pub fn parse_channels(
+    channels_cmd: &str,
+    layout: &ChannelLayout,
+) -> Result<(Vec<bool>, String), Box<dyn std::error::Error>> {
+    let n = layout.num_channels();
+    let mut enabled = vec![false; n];
+
+    for token in channels_cmd.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        for i in find_channel(token, layout)? {
+            if i >= n {
+                return Err(format!("channel [{}] is not in the layout [{}]", token, layout.name).into());
+            }
+            enabled[i] = true;
+        }
+    }
+
+    if !enabled.contains(&true) {
+        return Err(format!("no channel is selected [{}]", channels_cmd).into());
+    }
+
+    let mut filename_ch = String::new();
+    if layout.name != "stereo" {
+        filename_ch.push_str(&format!("_{}", layout.name));
+    }
+
+    if enabled.contains(&false) {
+        let selected = match channels_cmd.to_uppercase().as_str() {
+            "L" => "l".to_string(),
+            "R" => "r".to_string(),
+            _ => layout.names.iter()
+                .zip(&enabled)
+                .filter(|(_, &e)| e)
+                .map(|(name, _)| name.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+        };
+        filename_ch.push_str(&format!("_{}_only", selected));
+    }
+
+    Ok((enabled, filename_ch))
+}
+
+// comma separated values in the order of the channels, missing values are zero
+fn parse_channel_values(
+    cmd: &str,
+    layout: &ChannelLayout,
+    parse: impl Fn(&str) -> Result<f64, Box<dyn std::error::Error>>,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let mut values = vec![0.0; layout.num_channels()];
+    let tokens: Vec<&str> = cmd.split(',').map(|t| t.trim()).collect();
+    if tokens.len() > values.len() {
+        return Err(format!("too many values [{}] for the layout [{}]", cmd, layout.name).into());
+    }
+
+    for (value, token) in values.iter_mut().zip(tokens) {
+        if !token.is_empty() {
+            *value = parse(token)?;
+        }
+    }
+
+    Ok(values)
+}
+
+// phase offset, delay and polarity of each channel
+pub fn apply_channel_controls(
+    samples: &mut [Vec<f64>],
+    options: &CommonOptions,
+    layout: &ChannelLayout,
+    fs: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(cmd) = &options.phase {
+        let phases = parse_channel_values(cmd, layout, |t| {
+            t.parse::<f64>().map_err(|_| format!("cannot parse phase [{}]", t).into())
+        })?;
+
+        for (ch, deg) in samples.iter_mut().zip(phases) {
+            if deg != 0.0 {
+                let phase = vec![deg.to_radians(); ch.len()];
+                *ch = modurate::apply_phase(ch, &phase);
+            }
+        }
+    }
+
+    if let Some(cmd) = &options.delay {
+        let delays = parse_channel_values(cmd, layout, |t| gen::parse_samples(t, fs))?;
+
+        if delays.iter().any(|&d| d < 0.0) {
+            return Err("delay must not be negative".into());
+        }
+
+        // all channels are extended by the maximum delay to keep the end of the signals
+        let points: Vec<usize> = delays.iter().map(|d| d.round() as usize).collect();
+        let max_points = points.iter().copied().max().unwrap_or(0);
+        for (i, ch) in samples.iter_mut().enumerate() {
+            let points = points.get(i).copied().unwrap_or(0);
+            ch.splice(0..0, std::iter::repeat_n(0.0, points));
+            ch.resize(ch.len() + max_points - points, 0.0);
+        }
+    }
+
+    if let Some(cmd) = &options.invert {
+        let (inverted, _) = parse_channels(cmd, layout)?;
+
+        for (ch, _) in samples.iter_mut().zip(inverted).filter(|(_, inv)| *inv) {
+            for v in ch.iter_mut() {
+                *v = -*v;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use hound::WavSpec;
+use rustfft::{FftPlanner, num_complex::Complex, num_traits::Zero};
+
+use crate::commands::{self, conv::ConvOptions};
+use crate::fileio;
+
+// overlap-add convolution, the fft size is twice the IR length at least
+pub fn fast_convolution(x: &[f64], h: &[f64]) -> Vec<f64> {
+    if x.is_empty() || h.is_empty() {
+        return Vec::new();
+    }
+
+    let out_len = x.len() + h.len() - 1;
+    let n = (2 * h.len()).next_power_of_two();
+    let block_len = n - h.len() + 1;
+
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(n);
+    let ifft = planner.plan_fft_inverse(n);
+
+    let mut h_spectrum: Vec<Complex<f64>> = vec![Complex::zero(); n];
+    for (c, &v) in h_spectrum.iter_mut().zip(h) {
+        c.re = v;
+    }
+    fft.process(&mut h_spectrum);
+
+    let mut output = vec![0.0; out_len];
+    let mut buf: Vec<Complex<f64>> = vec![Complex::zero(); n];
+
+    for (b, block) in x.chunks(block_len).enumerate() {
+        buf.fill(Complex::zero());
+        for (c, &v) in buf.iter_mut().zip(block) {
+            c.re = v;
+        }
+
+        fft.process(&mut buf);
+        for (c, hc) in buf.iter_mut().zip(&h_spectrum) {
+            *c *= hc;
+        }
+        ifft.process(&mut buf);
+
+        let offset = b * block_len;
+        for (y, c) in output.iter_mut().skip(offset).zip(&buf) {
+            *y += c.re / n as f64;
+        }
+    }
+
+    output
+}
+
+pub fn convolve(
+    options: &ConvOptions,
+    input: &[Vec<f64>],
+    spec: &WavSpec,
+) -> Result<(Vec<Vec<f64>>, WavSpec), Box<dyn std::error::Error>> {
+    let (ir, ir_spec) = fileio::read_wav_file(options.ir.as_str())?;
+    if ir_spec.sample_rate != spec.sample_rate {
+        return Err(format!(
+            "sample rate mismatch: input {} Hz, IR {} Hz",
+            spec.sample_rate, ir_spec.sample_rate
+        ).into());
+    }
+
+    let num_ch = match (input.len(), ir.len()) {
+        (i, h) if i == h => i,
+        (i, 1) => i,
+        (1, h) => h,
+        (i, h) => {
+            return Err(format!("number of channels mismatch: input {}ch, IR {}ch", i, h).into());
+        }
+    };
+
+    let input_len = input.first().map(|x| x.len()).unwrap_or(0);
+    let mut output = Vec::with_capacity(num_ch);
+    for ch in 0..num_ch {
+        let x = &input[ch % input.len()];
+        let h = &ir[ch % ir.len()];
+
+        let mut y = fast_convolution(x, h);
+        if options.mode == "same" {
+            y.truncate(input_len);
+        }
+        output.push(y);
+    }
+
+    let max_value = output.iter()
+        .flatten()
+        .map(|y| y.abs())
+        .fold(0.0, f64::max);
+
+    if options.normalize && max_value > 0.0 {
+        for y in output.iter_mut().flatten() {
+            *y *= commands::AMP_MAX / max_value;
+        }
+    }else if max_value > commands::AMP_MAX {
+        println!("warning: output is clipped (peak {:.2}). use --normalize to avoid clipping.", max_value);
+    }
+
+    let out_spec = WavSpec {
+        channels: num_ch as u16,
+        ..*spec
+    };
+
+    Ok((output, out_spec))
+}
@@ -2,31 +2,38 @@ use std::{collections::VecDeque};
 use std::f64::consts::PI;
 use rustfft::{ FftPlanner, num_complex::Complex, num_traits::Zero};
 use rtaper::{WindowType, TaperSpec};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-use crate::commands::common::{self, TaperSpecOptions};
+use crate::commands::common::{self, TaperSpecOptions, TuningOptions};
+use crate::commands::gen::SweepOptions;
+use crate::fileio;
 use crate::processing;
 
 pub struct SignalSpec {
     pub amp: f64,
+    pub rms: Option<f64>,
     pub ch: String,
     pub fs: f64,
     pub d: f64,
     pub taper_spec: Option<TaperSpec>,
 }
 
+fn parse_window_type(name: &str) -> WindowType {
+    match name {
+        "linear" => { WindowType::Linear }
+        "hann" => { WindowType::Hann }
+        "cos" => { WindowType::Cosine }
+        "blackman" => { WindowType::Blackman }
+        _ => { WindowType::Linear }
+    }
+}
+
 pub fn get_taper_spec(opt: Option<&TaperSpecOptions>) -> Option<TaperSpec> {
     match opt {
         Some(opt) => {
-            let taper_type = match opt.window_type.as_str() {
-                "linear" => { WindowType::Linear }
-                "hann" => { WindowType::Hann }
-                "cos" => { WindowType::Cosine }
-                "blackman" => { WindowType::Blackman }
-                _ => { WindowType::Linear }
-            };
-        
             Some(TaperSpec {
-                taper_type: taper_type,
+                taper_type: parse_window_type(&opt.window_type),
                 taper_length: opt.length_of_taper,
             })
         }
@@ -58,6 +65,129 @@ pub fn parse_freq(freq_cmd: &str) -> Result<f64, Box<dyn std::error::Error>> {
     }
 }
 
+// e.g. C, F#, Bb -> pitch class (C = 0), the rest of the string and the label for filenames
+fn parse_pitch_class(cmd: &str) -> Option<(i32, &str, String)> {
+    let mut chars = cmd.chars();
+    let letter = chars.next()?.to_ascii_uppercase();
+    let mut pc = match letter {
+        'C' => 0, 'D' => 2, 'E' => 4, 'F' => 5, 'G' => 7, 'A' => 9, 'B' => 11,
+        _ => return None,
+    };
+
+    let mut label = letter.to_string();
+    let mut rest = chars.as_str();
+    loop {
+        if let Some(r) = rest.strip_prefix('#') {
+            pc += 1;
+            label.push('s');
+            rest = r;
+        }else if let Some(r) = rest.strip_prefix('b') {
+            pc -= 1;
+            label.push('b');
+            rest = r;
+        }else {
+            break;
+        }
+    }
+
+    Some((pc, rest, label))
+}
+
+// e.g. A4, C#3, Bb2 -> MIDI note number (A4 = 69) and the label for filenames (A4, Cs3, Bb2)
+pub fn parse_note(cmd: &str) -> Option<(i32, String)> {
+    let (pc, rest, label) = parse_pitch_class(cmd)?;
+    let octave = rest.parse::<i32>().ok()?;
+    Some(((octave + 1) * 12 + pc, format!("{}{}", label, octave)))
+}
+
+// frequency ratios of the 12 degrees from the tonic
+fn tuning_ratios(system: &str) -> [f64; 12] {
+    match system {
+        "just" => [
+            1.0, 16.0 / 15.0, 9.0 / 8.0, 6.0 / 5.0, 5.0 / 4.0, 4.0 / 3.0,
+            45.0 / 32.0, 3.0 / 2.0, 8.0 / 5.0, 5.0 / 3.0, 9.0 / 5.0, 15.0 / 8.0,
+        ],
+        "pythagorean" => [
+            1.0, 256.0 / 243.0, 9.0 / 8.0, 32.0 / 27.0, 81.0 / 64.0, 4.0 / 3.0,
+            729.0 / 512.0, 3.0 / 2.0, 128.0 / 81.0, 27.0 / 16.0, 16.0 / 9.0, 243.0 / 128.0,
+        ],
+        _ => std::array::from_fn(|i| 2.0_f64.powf(i as f64 / 12.0)),
+    }
+}
+
+// the scale is built on the tonic and scaled so that A4 is the reference
+fn note_frequency(midi: i32, a4: f64, system: &str, tonic: i32) -> f64 {
+    let ratios = tuning_ratios(system);
+    let pitch = |m: i32| ratios[(m - tonic).rem_euclid(12) as usize] * 2.0_f64.powi((m - tonic).div_euclid(12));
+    a4 * pitch(midi) / pitch(69)
+}
+
+// e.g. C, F#, Bb -> pitch class of the tonic
+pub fn parse_key(key: &str) -> Option<i32> {
+    parse_pitch_class(key)
+        .filter(|(_, rest, _)| rest.is_empty())
+        .map(|(pc, _, _)| pc)
+}
+
+// frequency of the MIDI note number in the tuning
+pub fn tuned_frequency(midi: i32, tuning: &TuningOptions) -> Result<f64, Box<dyn std::error::Error>> {
+    let tonic = parse_key(&tuning.key).ok_or_else(|| format!("cannot parse key [{}]", tuning.key))?;
+    Ok(note_frequency(midi, tuning.a4, &tuning.tuning, tonic))
+}
+
+// frequency in Hz or a note name of the tuning
+pub fn parse_tuned_freq(freq_cmd: &str, tuning: &TuningOptions) -> Result<f64, Box<dyn std::error::Error>> {
+    match parse_note(freq_cmd) {
+        Some((midi, _)) => tuned_frequency(midi, tuning),
+        None => parse_freq(freq_cmd),
+    }
+}
+
+// label of the note for filenames, None for a frequency in Hz
+pub fn note_label(freq_cmd: &str) -> Option<String> {
+    parse_note(freq_cmd).map(|(_, label)| label)
+}
+
+fn db_to_linear(db: f64) -> f64 {
+    10.0_f64.powf(db / 20.0)
+}
+
+// returns the linear amplitude and whether it is an RMS value
+pub fn parse_amplitude(amp_cmd: &str) -> Result<(f64, bool), Box<dyn std::error::Error>> {
+    match amp_cmd.parse::<f64>() {
+        Ok(val) => { Ok((val, false)) }
+        Err(_) => {
+            if let Ok(val) = strip_suffix_and_parse_f64(amp_cmd, "dBFSrms")    { Ok((db_to_linear(val), true)) }
+            else if let Ok(val) = strip_suffix_and_parse_f64(amp_cmd, "dBrms") { Ok((db_to_linear(val), true)) }
+            else if let Ok(val) = strip_suffix_and_parse_f64(amp_cmd, "dBFS") { Ok((db_to_linear(val), false)) }
+            else if let Ok(val) = strip_suffix_and_parse_f64(amp_cmd, "dB")   { Ok((db_to_linear(val), false)) }
+            else {
+                return Err(format!("cannot parse amplitude [{}]", amp_cmd).into())
+            }
+        }
+    }
+}
+
+pub fn scale_to_rms(samples: &mut [f64], rms: f64) {
+    if samples.is_empty() {
+        return;
+    }
+
+    let current = (samples.iter().map(|v| v * v).sum::<f64>() / samples.len() as f64).sqrt();
+    if current == 0.0 {
+        return;
+    }
+
+    for v in samples.iter_mut() {
+        *v *= rms / current;
+    }
+
+    let max_value = samples.iter().map(|v| v.abs()).fold(0.0, f64::max);
+    if max_value > 1.0 {
+        println!("warning: output is clipped (peak {:.2}) to meet the RMS target.", max_value);
+    }
+}
+
 pub fn parse_duration(duration_cmd: &str) -> Result<f64, Box<dyn std::error::Error>> {
     match duration_cmd.parse::<f64>() {
         Ok(val) => { Ok(val) }
@@ -77,13 +207,41 @@ pub fn parse_duration(duration_cmd: &str) -> Result<f64, Box<dyn std::error::Err
     }
 }
 
-fn byte_to_second(total_bytes: f64, fs: f64) -> f64 {
+// seconds or samples with the suffix "smp", returns the number of samples
+pub fn parse_samples(cmd: &str, fs: f64) -> Result<f64, Box<dyn std::error::Error>> {
+    match cmd.to_lowercase().strip_suffix("smp") {
+        Some(val) => val.trim().parse::<f64>()
+            .map_err(|_| format!("cannot parse samples [{}]", cmd).into()),
+        None => Ok(parse_duration(cmd)? * fs),
+    }
+}
+
+// position of an impulse or a step, returns the number of samples
+pub fn parse_position(cmd: &str, fs: f64) -> Result<usize, Box<dyn std::error::Error>> {
+    let position = parse_samples(cmd, fs)?;
+    if position < 0.0 {
+        return Err(format!("position must not be negative [{}]", cmd).into());
+    }
+    Ok(position as usize)
+}
+
+// Hz or BPM, returns the rate in Hz
+pub fn parse_rate(rate_cmd: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    if let Ok(val) = strip_suffix_and_parse_f64(rate_cmd, "bpm") {
+        return Ok(val / 60.0);
+    }
+    match rate_cmd.parse::<f64>() {
+        Ok(val) => { Ok(val) }
+        Err(_) => { parse_freq(rate_cmd) }
+    }
+}
+
+fn byte_to_second(total_bytes: f64, fs: f64, bits_per_sample: u16, channels: usize) -> f64 {
     const WAV_HEADER_SIZE: f64 = 44.0;
-    const CHANNELS: f64 = 2.0;
 
     let data_bytes = total_bytes - WAV_HEADER_SIZE;
-    let bytes_per_sample = (processing::BITS_PER_SAMPLE as f64) / 8.0;
-    let frame_size = bytes_per_sample * CHANNELS; // bytes per frame
+    let bytes_per_sample = (bits_per_sample as f64) / 8.0;
+    let frame_size = bytes_per_sample * channels as f64; // bytes per frame
 
     let num_frames = data_bytes / frame_size;
     let duration_sec = num_frames / fs;
@@ -110,7 +268,9 @@ pub fn parse_filesize(filesize_cmd: &str, opt: &common::CommonOptions) -> Result
         }
     }?;
 
-    Ok(byte_to_second(s, opt.rate_of_sample))
+    let (bits_per_sample, _) = fileio::parse_sample_format(&opt.bits)?;
+    let layout = processing::channel::parse_layout(&opt.layout)?;
+    Ok(byte_to_second(s, opt.rate_of_sample, bits_per_sample, layout.num_channels()))
 }
 
 fn do_apply_taper_end(samples: &mut Vec<f64>, taper_spec: &Option<TaperSpec>,
@@ -151,26 +311,238 @@ pub fn generate_sine_wave(spec: &SignalSpec, freq: f64) -> Result<Vec<f64>, Box<
     Ok(samples)
 }
 
-fn generate_white_noise(spec: &SignalSpec) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+pub fn parse_freq_list(list_cmd: &str, tuning: &TuningOptions) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    list_cmd.split(',')
+        .map(|f| f.trim())
+        .filter(|f| !f.is_empty())
+        .map(|f| parse_tuned_freq(f, tuning))
+        .collect()
+}
+
+// n frequencies from s to e, spacing is log or linear
+pub fn spaced_frequencies(s: f64, e: f64, n: usize, spacing: &str) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if s <= 0.0 || e < s {
+        return Err(format!("invalid frequency range {} Hz to {} Hz", s, e).into());
+    }
+
+    match n {
+        0 => { Err("number of frequencies must be greater than zero".into()) }
+        1 => { Ok(vec![s]) }
+        _ => {
+            let mut freqs: Vec<f64> = match spacing {
+                "log" => {
+                    let ratio = (e / s).ln() / (n - 1) as f64;
+                    (0..n).map(|i| s * (ratio * i as f64).exp()).collect()
+                }
+                "linear" => {
+                    let step = (e - s) / (n - 1) as f64;
+                    (0..n).map(|i| s + step * i as f64).collect()
+                }
+                _ => return Err(format!("unknown spacing [{}]", spacing).into()),
+            };
+            freqs[n - 1] = e;
+            Ok(freqs)
+        }
+    }
+}
+
+// tones at the exact multiples of fs / n are periodic in a block of n points
+pub fn snap_to_fft_bins(freqs: &[f64], fs: f64, n: usize) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if n == 0 {
+        return Err("FFT size must be greater than zero".into());
+    }
+
+    let df = fs / n as f64;
+    Ok(freqs.iter()
+        .map(|f| (f / df).round().max(1.0) * df)
+        .collect())
+}
+
+// the phases of schroeder and newman are given by the frequencies of the sorted tones,
+// which are assumed to lie on the grid of the minimum spacing (e.g. harmonics of f0)
+pub fn multitone_phases(freqs: &[f64], phase_type: &str, seed: u64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let n = freqs.len() as f64;
+    let spacing = freqs.windows(2)
+        .map(|w| w[1] - w[0])
+        .fold(f64::INFINITY, f64::min);
+    // offset of the tone from the lowest one in the grid spacing, k - 1 for the harmonics
+    let index = |f: f64| if spacing.is_finite() { (f - freqs[0]) / spacing } else { 0.0 };
+
+    match phase_type {
+        "zero" => { Ok(vec![0.0; freqs.len()]) }
+        "random" => {
+            let mut rng = create_rng(seed);
+            Ok(freqs.iter().map(|_| 2.0 * PI * rng.random::<f64>()).collect())
+        }
+        // -pi * k(k-1) / n for the harmonics k = 1, ..., n
+        "schroeder" => {
+            Ok(freqs.iter()
+                .map(|&f| -2.0 * PI * freqs.iter().take_while(|&&l| l < f).map(|&l| index(f) - index(l)).sum::<f64>() / n)
+                .collect())
+        }
+        // pi * (k-1)^2 / n for the harmonics k = 1, ..., n
+        "newman" => { Ok(freqs.iter().map(|&f| PI * index(f).powi(2) / n).collect()) }
+        _ => { Err(format!("unknown type of phase [{}]", phase_type).into()) }
+    }
+}
+
+// equal amplitude tones, the peak of the sum is scaled to the amplitude.
+// a periodic signal is not tapered to keep the periodicity
+pub fn generate_multitone(spec: &SignalSpec, freqs: &[f64], phases: &[f64], periodic: bool) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let sample_count = (spec.d * spec.fs) as usize;
+    let mut samples = vec![0.0; sample_count];
+    for (f, phase) in freqs.iter().zip(phases) {
+        let w = 2.0 * PI * f / spec.fs;
+        for (i, v) in samples.iter_mut().enumerate() {
+            *v += (w * i as f64 + phase).sin();
+        }
+    }
+
+    let peak = samples.iter().map(|v| v.abs()).fold(0.0, f64::max);
+    let rms = (samples.iter().map(|v| v * v).sum::<f64>() / sample_count.max(1) as f64).sqrt();
+    if rms > 0.0 {
+        println!("tones: {}, crest factor: {:.2} dB", freqs.len(), 20.0 * (peak / rms).log10());
+    }
+    normalize_peak(&mut samples, spec.amp);
+
+    if !periodic {
+        do_apply_taper_both(&mut samples, &spec.taper_spec)?;
+    }
+    Ok(samples)
+}
+
+// each step is tapered individually, the gap is inserted between the steps
+pub fn generate_stepped_sine(
+    spec: &SignalSpec,
+    freqs: &[f64],
+    dwells: &[f64],
+    gap: f64,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if gap < 0.0 || dwells.iter().any(|&d| d <= 0.0) {
+        return Err("dwell time must be greater than zero and gap must not be negative".into());
+    }
+
+    let gap_count = (gap * spec.fs) as usize;
+    let mut samples = Vec::new();
+    for (i, (&freq, &dwell)) in freqs.iter().zip(dwells).enumerate() {
+        if i > 0 {
+            samples.extend(std::iter::repeat_n(0.0, gap_count));
+        }
+
+        let mut step = generate_tone(spec.amp, &[freq], spec.fs, (dwell * spec.fs) as usize);
+        do_apply_taper_both(&mut step, &spec.taper_spec)?;
+        samples.extend(step);
+    }
+
+    Ok(samples)
+}
+
+pub fn create_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+// unit variance except uniform and binary, which have the peak of 1
+fn draw_noise_sample(rng: &mut ChaCha8Rng, distribution: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    match distribution {
+        "uniform" => { Ok(rng.random::<f64>() * 2.0 - 1.0) }
+        "gaussian" => {
+            // Box-Muller transform
+            let u1 = 1.0 - rng.random::<f64>();
+            let u2 = rng.random::<f64>();
+            Ok((-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos())
+        }
+        "laplacian" => {
+            let u = rng.random::<f64>() - 0.5;
+            Ok(-u.signum() * (1.0 - 2.0 * u.abs()).ln() / 2.0_f64.sqrt())
+        }
+        "binary" => { Ok(if rng.random::<bool>() { 1.0 } else { -1.0 }) }
+        _ => { Err(format!("unknown distribution [{}]", distribution).into()) }
+    }
+}
+
+fn normalize_peak(samples: &mut [f64], amp: f64) {
+    let max_value = samples.iter()
+        .map(|v| v.abs())
+        .fold(0.0, f64::max);
+
+    if max_value > 0.0 {
+        for v in samples.iter_mut() {
+            *v *= amp / max_value;
+        }
+    }
+}
+
+fn generate_white_noise(
+    spec: &SignalSpec,
+    rng: &mut ChaCha8Rng,
+    distribution: &str,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
     let sample_count = (spec.d * spec.fs) as usize;
     let mut samples = Vec::with_capacity(sample_count);
 
     for _ in 0..sample_count {
-        let noise = spec.amp * (rand::random::<f64>() * 2.0 - 1.0);
+        let noise = spec.amp * draw_noise_sample(rng, distribution)?;
         samples.push(noise);
     }
 
+    // unbounded distributions are scaled by the actual peak
+    if distribution == "gaussian" || distribution == "laplacian" {
+        normalize_peak(&mut samples, spec.amp);
+    }
+
     Ok(samples)
 }
 
-fn generate_pink_noise(spec: &SignalSpec) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
-    let white_noise = generate_white_noise(spec)?
+pub fn parse_crest_factor(cf_cmd: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    match parse_amplitude(cf_cmd)? {
+        (cf, false) if cf >= 1.0 => Ok(cf),
+        _ => Err(format!("crest factor must be 1 (0dB) or more [{}]", cf_cmd).into()),
+    }
+}
+
+// peak / RMS after clipping at the limit
+fn clipped_crest_factor(samples: &[f64], limit: f64) -> f64 {
+    let power = samples.iter().map(|v| v.abs().min(limit).powi(2)).sum::<f64>() / samples.len() as f64;
+    limit / power.sqrt()
+}
+
+// clip the peaks so that peak / RMS of the result is the crest_factor, then scale the peak to amp.
+// the crest factor after clipping increases with the limit, which is found by bisection
+fn limit_crest_factor(samples: &mut [f64], crest_factor: f64, amp: f64) {
+    const ITERATIONS: usize = 100;
+
+    let peak = samples.iter().map(|v| v.abs()).fold(0.0, f64::max);
+    if peak == 0.0 || clipped_crest_factor(samples, peak) <= crest_factor {
+        return normalize_peak(samples, amp);
+    }
+
+    let (mut low, mut high) = (0.0, peak);
+    for _ in 0..ITERATIONS {
+        let mid = (low + high) / 2.0;
+        if clipped_crest_factor(samples, mid) < crest_factor {
+            low = mid;
+        }else {
+            high = mid;
+        }
+    }
+
+    for v in samples.iter_mut() {
+        *v = v.clamp(-high, high);
+    }
+
+    normalize_peak(samples, amp);
+}
+
+// shape the spectrum of white noise, gain is the amplitude response for the frequency in Hz
+fn generate_colored_noise(
+    spec: &SignalSpec,
+    rng: &mut ChaCha8Rng,
+    distribution: &str,
+    gain: impl Fn(f64) -> f64,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let white_noise = generate_white_noise(spec, rng, distribution)?
         .into_iter()
-        .enumerate()
-        .map(|(_, real)| {
-            let c = Complex::new(real, 0.0);
-            c
-        })
+        .map(|real| Complex::new(real, 0.0))
         .collect::<Vec<_>>();
     let sample_count = white_noise.len();
 
@@ -181,19 +553,19 @@ fn generate_pink_noise(spec: &SignalSpec) -> Result<Vec<f64>, Box<dyn std::error
 
     for (i, freq) in spectrum.iter_mut().enumerate() {
         if i == 0 {
+            *freq = Complex::zero(); // remove DC
             continue;
         }
-        let f = i as f64;
-        let scaling_factor = 1.0 / f.sqrt();
-        freq.re *= scaling_factor;
-        freq.im *= scaling_factor;
+        // negative frequencies are scaled symmetrically
+        let f = i.min(sample_count - i) as f64 * spec.fs / sample_count as f64;
+        *freq *= gain(f);
     }
 
     let ifft = planner.plan_fft_inverse(sample_count);
     ifft.process(&mut spectrum);
 
     let max_value = spectrum.iter()
-        .map(|c| (c.re.powi(2) + c.im.powi(2)).sqrt())
+        .map(|c| c.re.abs())
         .fold(0.0, f64::max);
 
     let output = spectrum.iter()
@@ -203,17 +575,188 @@ fn generate_pink_noise(spec: &SignalSpec) -> Result<Vec<f64>, Box<dyn std::error
     Ok(output)
 }
 
-pub fn generate_noise(spec: &SignalSpec, noise_type: &str) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
-    let mut samples = match noise_type {
-        "white" => { generate_white_noise(spec) }?,
-        "pink" => { generate_pink_noise(spec) }?,
+// @ref IEC 61672-1
+fn a_weighting(f: f64) -> f64 {
+    let f2 = f * f;
+    let ra = 12194.0_f64.powi(2) * f2 * f2
+        / ((f2 + 20.6_f64.powi(2))
+            * ((f2 + 107.7_f64.powi(2)) * (f2 + 737.9_f64.powi(2))).sqrt()
+            * (f2 + 12194.0_f64.powi(2)));
+
+    ra * 10.0_f64.powf(2.0 / 20.0)
+}
+
+// inverse A-weighting, limited below 20Hz
+fn grey_noise_gain(f: f64) -> f64 {
+    const GREY_LOW_LIMIT: f64 = 20.0; // Hz
+    1.0 / a_weighting(f.max(GREY_LOW_LIMIT))
+}
+
+// octave band (e.g. 1k-oct) or third octave band (e.g. 1k-third)
+pub fn parse_band(band_cmd: &str) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+    let (center, half_width) = if let Some(center) = band_cmd.strip_suffix("-oct") {
+        (center, 2.0_f64.powf(1.0 / 2.0))
+    }else if let Some(center) = band_cmd.strip_suffix("-third") {
+        (center, 2.0_f64.powf(1.0 / 6.0))
+    }else {
+        return Err(format!("cannot parse band [{}]", band_cmd).into());
+    };
+
+    let fc = parse_freq(center)?;
+    Ok((fc / half_width, fc * half_width))
+}
+
+pub fn generate_noise(
+    spec: &SignalSpec,
+    noise_type: &str,
+    distribution: &str,
+    crest_factor: Option<f64>,
+    band: Option<(f64, f64)>,
+    seed: u64,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let mut rng = create_rng(seed);
+    let gain: Option<fn(f64) -> f64> = match noise_type {
+        "white" => { None }
+        "pink" => { Some(|f| 1.0 / f.sqrt()) }
+        "brown" | "red" => { Some(|f| 1.0 / f) }
+        "blue" => { Some(|f| f.sqrt()) }
+        "violet" => { Some(|f| f) }
+        "grey" => { Some(grey_noise_gain) }
         &_ => { return Err("unknown noise type".into()) }
     };
 
+    let mut samples = match (gain, band) {
+        (None, None) => { generate_white_noise(spec, &mut rng, distribution)? }
+        (gain, band) => {
+            generate_colored_noise(spec, &mut rng, distribution, |f| {
+                let in_band = band.is_none_or(|(s, e)| s <= f && f <= e);
+                if in_band { gain.map_or(1.0, |g| g(f)) } else { 0.0 }
+            })?
+        }
+    };
+
+    if let Some(cf) = crest_factor {
+        limit_crest_factor(&mut samples, cf, spec.amp);
+    }
+
     do_apply_taper_both(&mut samples, &spec.taper_spec)?;
     Ok(samples)
 }
 
+// feedback taps of primitive polynomials (Xilinx XAPP052)
+fn default_mls_taps(order: u32) -> Option<Vec<u32>> {
+    let taps: &[u32] = match order {
+        2 => &[2, 1],
+        3 => &[3, 2],
+        4 => &[4, 3],
+        5 => &[5, 3],
+        6 => &[6, 5],
+        7 => &[7, 6],
+        8 => &[8, 6, 5, 4],
+        9 => &[9, 5],
+        10 => &[10, 7],
+        11 => &[11, 9],
+        12 => &[12, 6, 4, 1],
+        13 => &[13, 4, 3, 1],
+        14 => &[14, 5, 3, 1],
+        15 => &[15, 14],
+        16 => &[16, 15, 13, 4],
+        17 => &[17, 14],
+        18 => &[18, 11],
+        19 => &[19, 6, 2, 1],
+        20 => &[20, 17],
+        21 => &[21, 19],
+        22 => &[22, 21],
+        23 => &[23, 18],
+        24 => &[24, 23, 22, 17],
+        _ => return None,
+    };
+    Some(taps.to_vec())
+}
+
+pub fn mls_taps(order: u32, taps_cmd: &Option<String>) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
+    let taps = match taps_cmd {
+        Some(cmd) => cmd.split(',')
+            .map(|t| t.trim().parse::<u32>().map_err(|_| format!("cannot parse taps [{}]", cmd)))
+            .collect::<Result<Vec<u32>, _>>()?,
+        None => default_mls_taps(order).ok_or(format!("order {} of MLS is not supported", order))?,
+    };
+
+    if taps.iter().any(|&t| t == 0 || t > order) {
+        return Err(format!("taps must be between 1 and the order {}", order).into());
+    }
+    // the register is not invertible without the last stage
+    if !taps.contains(&order) {
+        return Err(format!("taps must include the order {}", order).into());
+    }
+
+    Ok(taps)
+}
+
+// one period of the binary sequence generated by a Fibonacci LFSR
+pub fn mls_sequence(order: u32, taps: &[u32], seed: u64) -> Result<Vec<bool>, Box<dyn std::error::Error>> {
+    let len = (1_u64 << order) - 1;
+    let mask = taps.iter().fold(0_u64, |m, t| m | 1 << (t - 1));
+    let initial = seed % len + 1;
+
+    let mut state = initial;
+    let mut bits = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        bits.push(state >> (order - 1) & 1 == 1);
+        let feedback = (state & mask).count_ones() as u64 & 1;
+        state = (state << 1 | feedback) & len;
+
+        // the period must be exactly 2^order - 1
+        if (state == initial) != (i == len - 1) {
+            return Err(format!("taps {:?} do not generate a maximum length sequence", taps).into());
+        }
+    }
+
+    Ok(bits)
+}
+
+// bit 1 is mapped to -amp and bit 0 to +amp
+pub fn generate_mls(
+    spec: &SignalSpec,
+    order: u32,
+    taps: &[u32],
+    seed: u64,
+    periods: usize,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let bits = mls_sequence(order, taps, seed)?;
+    println!("length: {}, periods: {}", bits.len(), periods);
+
+    Ok(bits.iter()
+        .map(|&b| if b { -spec.amp } else { spec.amp })
+        .cycle()
+        .take(bits.len() * periods)
+        .collect())
+}
+
+// sequence A, gap and sequence B, the sum of their autocorrelations is a delta
+pub fn generate_golay_pair(spec: &SignalSpec, length: usize, gap: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if !length.is_power_of_two() || length < 2 {
+        return Err(format!("length of the Golay pair must be a power of two, but {}", length).into());
+    }
+    if gap < 0.0 {
+        return Err("gap must not be negative".into());
+    }
+
+    let mut a = vec![spec.amp];
+    let mut b = vec![spec.amp];
+    while a.len() < length {
+        let next_a = [a.as_slice(), b.as_slice()].concat();
+        let next_b: Vec<f64> = a.iter().cloned().chain(b.iter().map(|v| -v)).collect();
+        a = next_a;
+        b = next_b;
+    }
+
+    let mut samples = a;
+    samples.extend(std::iter::repeat_n(0.0, (gap * spec.fs) as usize));
+    samples.extend(b);
+    Ok(samples)
+}
+
 fn design_linear_tsp_spectrum(n: usize, flip_sw: f64) -> Vec<Complex<f64>> {
     let j = n / 2;
     let mut up_tsp_complex: Vec<Complex<f64>> = vec![Complex::zero(); n];
@@ -256,19 +799,32 @@ fn design_log_tsp_spectrum(n: usize, flip_sw: f64) -> Vec<Complex<f64>> {
     up_tsp_complex
 }
 
+// bins out of the band are set to zero
+fn limit_tsp_band(spectrum: &mut [Complex<f64>], band: Option<(f64, f64)>, fs: f64) {
+    if let Some((s, e)) = band {
+        let n = spectrum.len();
+        for (k, c) in spectrum.iter_mut().enumerate() {
+            let f = k.min(n - k) as f64 * fs / n as f64;
+            if f < s || f > e {
+                *c = Complex::zero();
+            }
+        }
+    }
+}
+
 fn exec_generate_tsp(
     spec: &SignalSpec,
+    n: usize,
     design_tsp_spect: fn(usize, f64) -> Vec<Complex<f64>>,
     enable_flip: bool,
-) -> Result<(Vec<f64>, f64, usize), Box<dyn std::error::Error>> {
-    let n_samples = spec.d * spec.fs;
-    let pow = (n_samples).log2().ceil() as i32;
-    let n = 1 << pow;
+    band: Option<(f64, f64)>,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
     println!("n: {}, {} [s]", n, n as f64 / spec.fs);
     let flip_sw = if enable_flip { 1.0 } else { -1.0 };
 
     let mut up_tsp_real: Vec<f64> = vec![0.0; n];
     let mut up_tsp_complex = design_tsp_spect(n, flip_sw);
+    limit_tsp_band(&mut up_tsp_complex, band, spec.fs);
 
     let mut planner = FftPlanner::new();
     let fft = planner.plan_fft_inverse(n);
@@ -296,27 +852,44 @@ fn exec_generate_tsp(
         deque.rotate_left(shift);
     }
 
-    Ok((deque.into(), n as f64, n_samples as usize))
+    Ok(deque.into())
 }
 
-pub fn generate_tsp_signal(spec: &SignalSpec, tsp_type: &str, enable_flip: bool) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
-    match tsp_type {
+// with the length, each of the repeated periods is exactly N samples.
+// without it, N is the duration rounded up to a power of two
+pub fn generate_tsp_signal(
+    spec: &SignalSpec,
+    tsp_type: &str,
+    enable_flip: bool,
+    band: Option<(f64, f64)>,
+    length: Option<usize>,
+    repeat: usize,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let n_samples = spec.d * spec.fs;
+    let periodic = length.is_some();
+    let n = match length {
+        Some(n) => n,
+        None => 1 << (n_samples).log2().ceil() as i32,
+    };
+
+    let period = match tsp_type {
         "linear" => {
-            let (samples, n, n_samples) = exec_generate_tsp(&spec, design_linear_tsp_spectrum, enable_flip)?;
-            Ok(
+            let samples = exec_generate_tsp(spec, n, design_linear_tsp_spectrum, enable_flip, band)?;
+            if periodic {
+                samples
+            }else {
                 samples.iter()
                     .skip((n as f64 * 0.2) as usize)
                     .take(n_samples as usize)
                     .cloned()
                     .collect()
-            )
-        }
-        "log" => {
-            let (samples, _n, _n_samples) = exec_generate_tsp(&spec, design_log_tsp_spectrum, enable_flip)?;
-            Ok(samples)
+            }
         }
+        "log" => { exec_generate_tsp(spec, n, design_log_tsp_spectrum, enable_flip, band)? }
         _ => { return Err("unexpected type of tsp signal".into()); }
-    }
+    };
+
+    Ok(period.iter().cycle().take(period.len() * repeat.max(1)).cloned().collect())
 }
 
 fn generate_log_sweep_signal(spec: &SignalSpec, s: f64, e: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
@@ -335,6 +908,30 @@ fn generate_log_sweep_signal(spec: &SignalSpec, s: f64, e: f64) -> Result<Vec<f6
     Ok(samples)
 }
 
+// rate L of the synchronized swept sine (Novák et al.), s * L is rounded to an integer
+// so that the harmonics of the sweep are in phase with the sweep delayed by L * ln(k)
+pub fn sync_sweep_rate(s: f64, e: f64, d: f64) -> f64 {
+    (s * d / (e / s).ln()).round().max(1.0) / s
+}
+
+pub fn sync_sweep_duration(s: f64, e: f64, d: f64) -> f64 {
+    sync_sweep_rate(s, e, d) * (e / s).ln()
+}
+
+fn generate_sync_sweep_signal(spec: &SignalSpec, s: f64, e: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let l = sync_sweep_rate(s, e, spec.d);
+    let sample_count = (l * (e / s).ln() * spec.fs) as usize;
+    let mut samples = Vec::with_capacity(sample_count);
+
+    for n in 0..sample_count {
+        let t = n as f64 / spec.fs;
+        let phase = 2.0 * PI * s * l * ((t / l).exp() - 1.0);
+        samples.push(spec.amp * phase.sin());
+    }
+
+    Ok(samples)
+}
+
 fn generate_linear_sweep_signal(spec: &SignalSpec, s: f64, e: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
     let sample_count = (spec.d * spec.fs) as usize;
     let mut samples = Vec::with_capacity(sample_count);
@@ -357,6 +954,7 @@ pub fn generate_sweep_signal(
     let mut output = match sweep_type {
         "linear" => { generate_linear_sweep_signal(spec, s, e)? }
         "log" => { generate_log_sweep_signal(spec, s, e)? }
+        "sync" => { generate_sync_sweep_signal(spec, s, e)? }
         _=> { return Err("Unknown swept type".into()) }
     };
 
@@ -364,6 +962,104 @@ pub fn generate_sweep_signal(
     Ok(output)
 }
 
+// time at which the sweep reaches the frequency f
+fn sweep_time_at(sweep_type: &str, f: f64, s: f64, e: f64, d: f64) -> f64 {
+    let t = match sweep_type {
+        "log" | "sync" => { d * (f / s).ln() / (e / s).ln() }
+        _ => { d * (f - s) / (e - s) }
+    };
+    t.clamp(0.0, d)
+}
+
+// raised cosine fades on the band edges, defined by the instantaneous frequency of the sweep
+fn fade_sweep_band_edges(
+    samples: &mut [f64],
+    spec: &SignalSpec,
+    sweep_type: &str,
+    s: f64,
+    e: f64,
+    fade_in: Option<f64>,
+    fade_out: Option<f64>,
+) {
+    let t_in = fade_in.map(|f| sweep_time_at(sweep_type, f, s, e, spec.d));
+    let t_out = fade_out.map(|f| sweep_time_at(sweep_type, f, s, e, spec.d));
+
+    for (n, v) in samples.iter_mut().enumerate() {
+        let t = n as f64 / spec.fs;
+        if let Some(t_in) = t_in {
+            if t < t_in {
+                *v *= 0.5 * (1.0 - (PI * t / t_in).cos());
+            }
+        }
+        if let Some(t_out) = t_out {
+            if t > t_out && spec.d > t_out {
+                *v *= 0.5 * (1.0 + (PI * (t - t_out) / (spec.d - t_out)).cos());
+            }
+        }
+    }
+}
+
+// the sweep with the band-edge fades, without the silence padding
+pub fn generate_faded_sweep(
+    spec: &SignalSpec,
+    options: &SweepOptions,
+    s: f64,
+    e: f64,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let fade_in = options.fade_in.as_deref().map(|f| parse_tuned_freq(f, &options.tuning)).transpose()?;
+    let fade_out = options.fade_out.as_deref().map(|f| parse_tuned_freq(f, &options.tuning)).transpose()?;
+
+    let mut samples = generate_sweep_signal(spec, &options.type_of_sweep, s, e)?;
+    fade_sweep_band_edges(&mut samples, spec, &options.type_of_sweep, s, e, fade_in, fade_out);
+    Ok(samples)
+}
+
+pub fn pad_silence(samples: Vec<f64>, pre_cmd: &str, post_cmd: &str, fs: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let pre = parse_samples(pre_cmd, fs)?;
+    let post = parse_samples(post_cmd, fs)?;
+    if pre < 0.0 || post < 0.0 {
+        return Err("silence must not be negative".into());
+    }
+
+    let mut output = vec![0.0; pre.round() as usize];
+    output.extend(samples);
+    output.extend(std::iter::repeat_n(0.0, post.round() as usize));
+    Ok(output)
+}
+
+// magnitude of the DTFT of x at the frequency f
+fn dtft_magnitude(x: &[f64], f: f64, fs: f64) -> f64 {
+    let w = 2.0 * PI * f / fs;
+    x.iter()
+        .enumerate()
+        .map(|(n, v)| Complex::from_polar(*v, -w * n as f64))
+        .sum::<Complex<f64>>()
+        .norm()
+}
+
+// time-reversed sweep, the log and sync sweeps are compensated by -6dB/oct (Farina).
+// the gain of the sweep and the inverse filter is unity at the geometric center of the band
+pub fn sweep_inverse_filter(sweep: &[f64], sweep_type: &str, s: f64, e: f64, fs: f64) -> Vec<f64> {
+    let n = sweep.len();
+    let ln_ratio = if sweep_type == "linear" { 0.0 } else { (e / s).ln() };
+
+    let mut inverse: Vec<f64> = sweep.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, v)| v * (-(i as f64) * ln_ratio / n as f64).exp())
+        .collect();
+
+    let fc = (s * e).sqrt();
+    let gain = dtft_magnitude(sweep, fc, fs) * dtft_magnitude(&inverse, fc, fs);
+    if gain > 0.0 {
+        for v in inverse.iter_mut() {
+            *v /= gain;
+        }
+    }
+
+    inverse
+}
+
 pub fn generate_pwm_signal(spec: &SignalSpec, freq: f64, duty: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
     let mut samples = vec![0.0; (spec.d * spec.fs) as usize];
     let period_samples = spec.fs / freq;
@@ -383,6 +1079,372 @@ pub fn generate_pwm_signal(spec: &SignalSpec, freq: f64, duty: f64) -> Result<Ve
     Ok(samples)
 }
 
+// residual of a unit step (-1 to 1) band-limited by 2nd order polynomial
+fn poly_blep(t: f64, dt: f64) -> f64 {
+    if t < dt {
+        let t = t / dt;
+        t + t - t * t - 1.0
+    }else if t > 1.0 - dt {
+        let t = (t - 1.0) / dt;
+        t * t + t + t + 1.0
+    }else {
+        0.0
+    }
+}
+
+// integrated poly_blep for the change of the slope
+fn poly_blamp(t: f64, dt: f64) -> f64 {
+    if t < dt {
+        let t = t / dt - 1.0;
+        -t.powi(3) / 3.0
+    }else if t > 1.0 - dt {
+        let t = (t - 1.0) / dt + 1.0;
+        t.powi(3) / 3.0
+    }else {
+        0.0
+    }
+}
+
+// one period of the waveform for the phase p in [0, 1), w is the duty (square) or the skew (triangle)
+fn naive_periodic_value(shape: &str, p: f64, w: f64) -> f64 {
+    match shape {
+        "square" => { if p < w { 1.0 } else { -1.0 } }
+        "triangle" => {
+            if p < w { -1.0 + 2.0 * p / w } else { 1.0 - 2.0 * (p - w) / (1.0 - w) }
+        }
+        _ => { 2.0 * p - 1.0 } // sawtooth
+    }
+}
+
+fn poly_blep_periodic_value(shape: &str, p: f64, w: f64, dt: f64) -> f64 {
+    let naive = naive_periodic_value(shape, p, w);
+    match shape {
+        "square" => { naive + poly_blep(p, dt) - poly_blep((p + 1.0 - w) % 1.0, dt) }
+        "triangle" => {
+            // the slope changes by 2 / (w (1 - w)) at the bottom and the top
+            let slope = 2.0 / (w * (1.0 - w));
+            naive + slope * dt * (poly_blamp(p, dt) - poly_blamp((p + 1.0 - w) % 1.0, dt))
+        }
+        _ => { naive - poly_blep(p, dt) }
+    }
+}
+
+// complex fourier coefficient of the n-th harmonic (x = 2 Re sum c_n e^{j 2pi n p})
+fn harmonic_coefficient(shape: &str, n: usize, w: f64) -> Complex<f64> {
+    let n = n as f64;
+    let e = Complex::new(0.0, -2.0 * PI * n * w).exp();
+    match shape {
+        "square" => { (1.0 - e) / Complex::new(0.0, PI * n) }
+        "triangle" => {
+            let slope = 2.0 / (w * (1.0 - w));
+            -slope * (1.0 - e) / (4.0 * PI * PI * n * n)
+        }
+        _ => { Complex::new(0.0, 1.0 / (PI * n)) }
+    }
+}
+
+pub fn generate_periodic_wave(
+    spec: &SignalSpec,
+    shape: &str,
+    freq: f64,
+    w: f64,
+    method: &str,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if freq <= 0.0 {
+        return Err("frequency must be greater than zero".into());
+    }
+
+    // avoid the division by zero of the skew
+    let w = if shape == "triangle" { w.clamp(1e-6, 1.0 - 1e-6) } else { w };
+    let sample_count = (spec.d * spec.fs) as usize;
+    let dt = freq / spec.fs;
+    let mut samples = Vec::with_capacity(sample_count);
+
+    match method {
+        "naive" | "polyblep" => {
+            for i in 0..sample_count {
+                let p = (i as f64 * dt).fract();
+                let value = if method == "naive" {
+                    naive_periodic_value(shape, p, w)
+                }else {
+                    poly_blep_periodic_value(shape, p, w, dt)
+                };
+                samples.push(spec.amp * value);
+            }
+        }
+        "additive" => {
+            let dc = if shape == "square" { 2.0 * w - 1.0 } else { 0.0 };
+            let harmonics: Vec<Complex<f64>> = (1..)
+                .take_while(|n| *n as f64 * freq < spec.fs / 2.0)
+                .map(|n| harmonic_coefficient(shape, n, w))
+                .collect();
+
+            for i in 0..sample_count {
+                let p = (i as f64 * dt).fract();
+                let value = harmonics.iter()
+                    .enumerate()
+                    .map(|(k, c)| (c * Complex::new(0.0, 2.0 * PI * (k + 1) as f64 * p).exp()).re)
+                    .sum::<f64>();
+                samples.push(dc + 2.0 * value);
+            }
+            // the Gibbs ringing overshoots the amplitude of the ideal waveform
+            normalize_peak(&mut samples, spec.amp);
+        }
+        _ => { return Err(format!("unknown synthesis method [{}]", method).into()) }
+    }
+
+    do_apply_taper_both(&mut samples, &spec.taper_spec)?;
+    Ok(samples)
+}
+
+// sum of the sine waves, the peak of the sum is the amplitude
+fn generate_tone(amp: f64, freqs: &[f64], fs: f64, sample_count: usize) -> Vec<f64> {
+    let amp = amp / freqs.len().max(1) as f64;
+    (0..sample_count)
+        .map(|n| {
+            let t = n as f64 / fs;
+            freqs.iter().map(|f| amp * (2.0 * PI * f * t).sin()).sum()
+        })
+        .collect()
+}
+
+fn dtmf_frequencies(digit: char) -> Option<[f64; 2]> {
+    const ROWS: [f64; 4] = [697.0, 770.0, 852.0, 941.0];
+    const COLUMNS: [f64; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+    const KEYS: [&str; 4] = ["123A", "456B", "789C", "*0#D"];
+
+    KEYS.iter()
+        .enumerate()
+        .find_map(|(row, keys)| keys.find(digit.to_ascii_uppercase()).map(|col| [ROWS[row], COLUMNS[col]]))
+}
+
+pub fn generate_dtmf(spec: &SignalSpec, digits: &str, tone: f64, pause: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if tone <= 0.0 || pause < 0.0 {
+        return Err("tone must be greater than zero and pause must not be negative".into());
+    }
+    if digits.is_empty() {
+        return Err("no DTMF digit is given".into());
+    }
+
+    let mut samples = Vec::new();
+    for (i, digit) in digits.chars().enumerate() {
+        let freqs = dtmf_frequencies(digit).ok_or(format!("unknown DTMF digit [{}]", digit))?;
+        if i > 0 {
+            samples.extend(std::iter::repeat_n(0.0, (pause * spec.fs) as usize));
+        }
+        samples.extend(generate_tone(spec.amp, &freqs, spec.fs, (tone * spec.fs) as usize));
+    }
+
+    Ok(samples)
+}
+
+// frequencies and cadence (on, off) of the call-progress tones, off of zero is a continuous tone
+struct CallProgressTone {
+    freqs: &'static [f64],
+    cadence: &'static [(f64, f64)],
+}
+
+fn call_progress_tone(preset: &str, region: &str) -> Option<CallProgressTone> {
+    let (freqs, cadence): (&'static [f64], &'static [(f64, f64)]) = match (preset, region) {
+        ("dial", "us") => (&[350.0, 440.0], &[(1.0, 0.0)]),
+        ("busy", "us") => (&[480.0, 620.0], &[(0.5, 0.5)]),
+        ("ringback", "us") => (&[440.0, 480.0], &[(2.0, 4.0)]),
+        ("dial", "eu") => (&[425.0], &[(1.0, 0.0)]),
+        ("busy", "eu") => (&[425.0], &[(0.5, 0.5)]),
+        ("ringback", "eu") => (&[425.0], &[(1.0, 4.0)]),
+        ("dial", "uk") => (&[350.0, 450.0], &[(1.0, 0.0)]),
+        ("busy", "uk") => (&[400.0], &[(0.375, 0.375)]),
+        ("ringback", "uk") => (&[400.0, 450.0], &[(0.4, 0.2), (0.4, 2.0)]),
+        ("dial", "jp") => (&[400.0], &[(1.0, 0.0)]),
+        ("busy", "jp") => (&[400.0], &[(0.5, 0.5)]),
+        ("ringback", "jp") => (&[400.0], &[(1.0, 2.0)]),
+        _ => return None,
+    };
+    Some(CallProgressTone { freqs, cadence })
+}
+
+// the cadence is repeated for the duration of the signal
+pub fn generate_call_progress_tone(spec: &SignalSpec, preset: &str, region: &str) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let tone = call_progress_tone(preset, region)
+        .ok_or(format!("unknown call-progress tone [{}] of the region [{}]", preset, region))?;
+
+    let sample_count = (spec.d * spec.fs) as usize;
+    let mut samples = generate_tone(spec.amp, tone.freqs, spec.fs, sample_count);
+
+    let period: f64 = tone.cadence.iter().map(|(on, off)| on + off).sum();
+    for (n, v) in samples.iter_mut().enumerate() {
+        let mut t = (n as f64 / spec.fs) % period;
+        for (on, off) in tone.cadence.iter() {
+            if t < on + off {
+                if t >= *on {
+                    *v = 0.0;
+                }
+                break;
+            }
+            t -= on + off;
+        }
+    }
+
+    Ok(samples)
+}
+
+// cutoff of the first-order low-pass filter on the square wave of DIM 30
+const DIM_LOWPASS_FREQ: f64 = 30_000.0;
+
+// two tones of the amplitude ratio f1:f2, the peak of the sum is scaled to the amplitude.
+// dim uses the band-limited square wave on f1, low-pass filtered at 30kHz
+pub fn generate_imd(spec: &SignalSpec, preset: &str, f1: f64, f2: f64, ratio: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if f1 <= 0.0 || f2 <= 0.0 || f1.max(f2) >= spec.fs / 2.0 {
+        return Err(format!("tone frequencies {} Hz and {} Hz are out of range", f1, f2).into());
+    }
+
+    let sample_count = (spec.d * spec.fs) as usize;
+    let mut first: Vec<f64> = if preset == "dim" {
+        (0..sample_count)
+            .map(|n| poly_blep_periodic_value("square", (n as f64 * f1 / spec.fs).fract(), 0.5, f1 / spec.fs))
+            .collect()
+    }else {
+        generate_tone(1.0, &[f1], spec.fs, sample_count)
+    };
+
+    if preset == "dim" && DIM_LOWPASS_FREQ < spec.fs / 2.0 {
+        let alpha = 1.0 - (-2.0 * PI * DIM_LOWPASS_FREQ / spec.fs).exp();
+        let mut y = 0.0;
+        for v in first.iter_mut() {
+            y += alpha * (*v - y);
+            *v = y;
+        }
+    }
+
+    let second = generate_tone(1.0 / ratio, &[f2], spec.fs, sample_count);
+    let mut samples: Vec<f64> = first.iter().zip(&second).map(|(a, b)| a + b).collect();
+    normalize_peak(&mut samples, spec.amp);
+
+    do_apply_taper_both(&mut samples, &spec.taper_spec)?;
+    Ok(samples)
+}
+
+// sine bursts of the cycles starting at zero phase, repeated every period.
+// the window covers the whole burst, i.e. the taper of the half length on both sides
+pub fn generate_burst(spec: &SignalSpec, freq: f64, cycles: f64, window: &str, period: Option<f64>) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if freq <= 0.0 || freq >= spec.fs / 2.0 {
+        return Err(format!("frequency of the burst {} Hz is out of range", freq).into());
+    }
+    if cycles <= 0.0 {
+        return Err("number of cycles must be greater than zero".into());
+    }
+
+    let burst_len = (cycles * spec.fs / freq).round() as usize;
+    let mut burst: Vec<f64> = (0..burst_len)
+        .map(|n| spec.amp * (2.0 * PI * freq * n as f64 / spec.fs).sin())
+        .collect();
+
+    if window != "rect" {
+        let taper_spec = TaperSpec {
+            taper_type: parse_window_type(window),
+            taper_length: burst_len / 2,
+        };
+        rtaper::apply_taper_both(&mut burst, &taper_spec)?;
+    }
+
+    let sample_count = (spec.d * spec.fs) as usize;
+    if burst_len > sample_count {
+        return Err(format!("burst of {} samples exceeds the duration", burst_len).into());
+    }
+
+    let step = match period {
+        Some(p) => {
+            let step = (p * spec.fs).round() as usize;
+            if step < burst_len {
+                return Err(format!("period {} sec is shorter than the burst", p).into());
+            }
+            step
+        }
+        None => sample_count,
+    };
+
+    let mut samples = vec![0.0; sample_count];
+    for start in (0..sample_count - burst_len + 1).step_by(step) {
+        samples[start..start + burst_len].copy_from_slice(&burst);
+    }
+
+    Ok(samples)
+}
+
+// sum of the sines of the same amplitude, the peak is scaled to the amplitude
+pub fn generate_chord(spec: &SignalSpec, freqs: &[f64]) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if freqs.is_empty() {
+        return Err("chord has no notes".into());
+    }
+    if let Some(f) = freqs.iter().find(|&&f| f <= 0.0 || f >= spec.fs / 2.0) {
+        return Err(format!("frequency {} Hz of the chord is out of range", f).into());
+    }
+
+    let mut samples = generate_tone(1.0, freqs, spec.fs, (spec.d * spec.fs) as usize);
+    normalize_peak(&mut samples, spec.amp);
+
+    do_apply_taper_both(&mut samples, &spec.taper_spec)?;
+    Ok(samples)
+}
+
+pub fn polarity_sign(polarity: &str) -> f64 {
+    if polarity == "negative" { -1.0 } else { 1.0 }
+}
+
+pub fn generate_impulse(spec: &SignalSpec, position: usize, width: usize, sign: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let sample_count = (spec.d * spec.fs) as usize;
+    if position >= sample_count {
+        return Err(format!("position {} is out of the signal of {} samples", position, sample_count).into());
+    }
+
+    let mut samples = vec![0.0; sample_count];
+    let end = (position + width.max(1)).min(sample_count);
+    for v in samples[position..end].iter_mut() {
+        *v = sign * spec.amp;
+    }
+
+    Ok(samples)
+}
+
+pub fn generate_step(spec: &SignalSpec, position: usize, sign: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let sample_count = (spec.d * spec.fs) as usize;
+    if position >= sample_count {
+        return Err(format!("position {} is out of the signal of {} samples", position, sample_count).into());
+    }
+
+    let mut samples = vec![0.0; sample_count];
+    for v in samples[position..].iter_mut() {
+        *v = sign * spec.amp;
+    }
+
+    Ok(samples)
+}
+
+// the first click is at the beginning, each click is at the nearest sample of its exact time
+pub fn generate_clicks(spec: &SignalSpec, rate: f64, width: usize, sign: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    if rate <= 0.0 {
+        return Err("rate of the clicks must be greater than zero".into());
+    }
+
+    let sample_count = (spec.d * spec.fs) as usize;
+    let mut samples = vec![0.0; sample_count];
+    let interval = spec.fs / rate;
+    let mut k = 0;
+    loop {
+        let position = (k as f64 * interval).round() as usize;
+        if position >= sample_count {
+            break;
+        }
+        let end = (position + width.max(1)).min(sample_count);
+        for v in samples[position..end].iter_mut() {
+            *v = sign * spec.amp;
+        }
+        k += 1;
+    }
+
+    Ok(samples)
+}
+
 pub fn generate_zeros(spec: &SignalSpec) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
     Ok(vec![0.0; (spec.d * spec.fs) as usize])
 }
@@ -0,0 +1,288 @@
+use hound::WavSpec;
+use rustfft::{FftPlanner, num_complex::Complex, num_traits::Zero};
+
+use crate::commands::{self, ir::IrOptions};
+use crate::fileio;
+use crate::processing::{self, conv, gen};
+
+// regularization of the spectral division relative to the mean stimulus power
+const REGULARIZATION: f64 = 1e-4;
+
+fn to_spectrum(x: &[f64], n: usize, planner: &mut FftPlanner<f64>) -> Vec<Complex<f64>> {
+    let mut spectrum: Vec<Complex<f64>> = vec![Complex::zero(); n];
+    for (c, &v) in spectrum.iter_mut().zip(x) {
+        c.re = v;
+    }
+    planner.plan_fft_forward(n).process(&mut spectrum);
+    spectrum
+}
+
+// Y / S of n points with Tikhonov regularization
+fn spectral_division(y: &[f64], s: &[f64], n: usize) -> Vec<f64> {
+    let mut planner = FftPlanner::new();
+    let mut y_spectrum = to_spectrum(y, n, &mut planner);
+    let s_spectrum = to_spectrum(s, n, &mut planner);
+
+    let mean_power = s_spectrum.iter()
+        .map(|c| c.norm_sqr())
+        .sum::<f64>() / n as f64;
+    let eps = REGULARIZATION * mean_power;
+
+    for (yc, sc) in y_spectrum.iter_mut().zip(&s_spectrum) {
+        *yc = *yc * sc.conj() / (sc.norm_sqr() + eps);
+    }
+
+    planner.plan_fft_inverse(n).process(&mut y_spectrum);
+    y_spectrum.iter().map(|c| c.re / n as f64).collect()
+}
+
+// the periods are assumed to be repeated back-to-back in the recording
+fn synchronous_average(x: &[f64], period: usize, periods: usize) -> Vec<f64> {
+    let mut output = vec![0.0; period];
+    for block in x.chunks(period).take(periods) {
+        for (o, v) in output.iter_mut().zip(block) {
+            *o += v / periods as f64;
+        }
+    }
+    output
+}
+
+fn fast_hadamard_transform(x: &mut [f64]) {
+    let mut h = 1;
+    while h < x.len() {
+        for i in (0..x.len()).step_by(2 * h) {
+            for j in i..i + h {
+                let (a, b) = (x[j], x[j + h]);
+                x[j] = a + b;
+                x[j + h] = a - b;
+            }
+        }
+        h *= 2;
+    }
+}
+
+// permutations of the input and the output of the Hadamard transform (Borish and Angell).
+// the state of the shift register at i is the window of the bits m[i], m[i-1], ..., m[i-order+1]
+fn mls_permutations(bits: &[bool], order: usize) -> (Vec<usize>, Vec<usize>) {
+    let len = bits.len();
+    let bit = |i: usize| bits[i % len] as usize;
+
+    let tag_s: Vec<usize> = (0..len)
+        .map(|i| (0..order).fold(0, |acc, j| acc | bit(len + i - j) << j))
+        .collect();
+
+    // m[i-k] is a linear function of the state at i, its coefficients are found at the unit states
+    let mut index = vec![0; order];
+    for (i, state) in tag_s.iter().enumerate() {
+        if state.is_power_of_two() {
+            index[state.trailing_zeros() as usize] = i;
+        }
+    }
+
+    let tag_l: Vec<usize> = (0..len)
+        .map(|k| (0..order).fold(0, |acc, j| acc | bit(len + index[j] - k) << j))
+        .collect();
+
+    (tag_s, tag_l)
+}
+
+// circular cross-correlation of one period with the MLS by the fast Hadamard transform
+fn mls_deconvolve(y: &[f64], stimulus: &[f64]) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let len = stimulus.len();
+    if !(len + 1).is_power_of_two() {
+        return Err(format!("length of the MLS must be 2^n - 1, but {}", len).into());
+    }
+
+    let order = (len + 1).trailing_zeros() as usize;
+    let bits: Vec<bool> = stimulus.iter().map(|&v| v < 0.0).collect();
+    let amp = stimulus.iter().map(|v| v.abs()).sum::<f64>() / len as f64;
+    let (tag_s, tag_l) = mls_permutations(&bits, order);
+
+    let mut permuted = vec![0.0; len + 1];
+    permuted[0] = -y.iter().take(len).sum::<f64>();
+    for (&tag, &v) in tag_s.iter().zip(y) {
+        permuted[tag] = v;
+    }
+
+    fast_hadamard_transform(&mut permuted);
+
+    Ok(tag_l.iter()
+        .map(|&tag| permuted[tag] / ((len + 1) as f64 * amp))
+        .collect())
+}
+
+fn generate_stimulus(options: &IrOptions, fs: f64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    let signal_spec = gen::SignalSpec {
+        amp: processing::value_verify(options.amplitude, commands::AMP_MIN, commands::AMP_MAX),
+        rms: None,
+        ch: "LR".to_string(),
+        fs,
+        d: gen::parse_duration(&options.duration)?,
+        taper_spec: gen::get_taper_spec(Some(&options.taper_opt)),
+    };
+
+    match options.type_of_stimulus.as_str() {
+        "log_tsp" => { gen::generate_tsp_signal(&signal_spec, "log", options.flip, None, None, 1) }
+        "linear_tsp" => { gen::generate_tsp_signal(&signal_spec, "linear", options.flip, None, None, 1) }
+        "log_sweep" | "linear_sweep" | "sync_sweep" => {
+            let sweep_type = options.type_of_stimulus.trim_end_matches("_sweep");
+            let s = gen::parse_tuned_freq(&options.startf, &options.tuning)?;
+            let e = gen::parse_tuned_freq(&options.endf, &options.tuning)?;
+            gen::generate_sweep_signal(&signal_spec, sweep_type, s, e)
+        }
+        "mls" => {
+            let seed = options.seed.ok_or("seed of the MLS is required to regenerate the stimulus")?;
+            let taps = gen::mls_taps(options.order, &options.taps)?;
+            gen::generate_mls(&signal_spec, options.order, &taps, seed, 1)
+        }
+        _ => { Err("unknown type of stimulus".into()) }
+    }
+}
+
+fn is_exponential_sweep(type_of_stimulus: &str) -> bool {
+    type_of_stimulus == "log_sweep" || type_of_stimulus == "sync_sweep"
+}
+
+fn read_stimulus(options: &IrOptions, spec: &WavSpec) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    match &options.stimulus {
+        Some(filename) => {
+            let (samples, stimulus_spec) = fileio::read_wav_file(filename)?;
+            if stimulus_spec.sample_rate != spec.sample_rate {
+                return Err(format!(
+                    "sample rate mismatch: recording {} Hz, stimulus {} Hz",
+                    spec.sample_rate, stimulus_spec.sample_rate
+                ).into());
+            }
+            samples.into_iter().next().ok_or_else(|| "stimulus has no channels".into())
+        }
+        None => { generate_stimulus(options, spec.sample_rate as f64) }
+    }
+}
+
+// returns the linear IR and the harmonic distortion IRs (2nd, 3rd, ...) of one channel
+fn deconvolve(
+    options: &IrOptions,
+    recording: &[f64],
+    stimulus: &[f64],
+    fs: f64,
+) -> Result<(Vec<f64>, Vec<Vec<f64>>), Box<dyn std::error::Error>> {
+    let period = stimulus.len();
+    let recording = if options.periods > 1 {
+        synchronous_average(recording, period, options.periods)
+    }else {
+        recording.to_vec()
+    };
+
+    if options.type_of_stimulus == "mls" {
+        return Ok((mls_deconvolve(&recording, stimulus)?, Vec::new()));
+    }
+
+    let is_log_sweep = options.stimulus.is_none() && is_exponential_sweep(&options.type_of_stimulus);
+    if !is_log_sweep {
+        let ir = if options.periods > 1 {
+            // steady-state response of a periodic excitation
+            spectral_division(&recording, stimulus, period)
+        }else {
+            let n = (recording.len() + period).next_power_of_two();
+            let mut ir = spectral_division(&recording, stimulus, n);
+            ir.truncate(recording.len());
+            ir
+        };
+        return Ok((ir, Vec::new()));
+    }
+
+    let s = gen::parse_tuned_freq(&options.startf, &options.tuning)?;
+    let e = gen::parse_tuned_freq(&options.endf, &options.tuning)?;
+    let inverse = gen::sweep_inverse_filter(stimulus, "log", s, e, fs);
+    let response = conv::fast_convolution(&recording, &inverse);
+
+    // the linear IR starts at the end of the inverse filter,
+    // the k-th harmonic IR precedes it by d * ln(k) / ln(e / s)
+    let d = period as f64 / fs;
+    let offset = |k: usize| (d * (k as f64).ln() / (e / s).ln() * fs).round() as usize;
+    let zero = period - 1;
+
+    let linear = response[zero..].to_vec();
+    let mut harmonics = Vec::with_capacity(options.harmonics);
+    for k in 2..options.harmonics + 2 {
+        let start = zero.saturating_sub(offset(k));
+        let end = zero.saturating_sub(offset(k - 1));
+        harmonics.push(response[start..end].to_vec());
+    }
+
+    Ok((linear, harmonics))
+}
+
+fn truncate_ir(samples: &mut [Vec<f64>], ir_len: Option<usize>) {
+    if let Some(len) = ir_len {
+        for ch in samples.iter_mut() {
+            ch.truncate(len);
+        }
+    }
+}
+
+pub fn measure_ir(
+    options: &IrOptions,
+    recording: &[Vec<f64>],
+    spec: &WavSpec,
+) -> Result<(Vec<Vec<f64>>, Vec<Vec<Vec<f64>>>), Box<dyn std::error::Error>> {
+    if options.periods == 0 {
+        return Err("number of periods must be greater than zero".into());
+    }
+    if options.harmonics > 0 && (options.stimulus.is_some() || !is_exponential_sweep(&options.type_of_stimulus)) {
+        return Err("harmonic separation is only available for a generated log_sweep or sync_sweep".into());
+    }
+
+    if recording.iter().any(|ch| ch.is_empty()) {
+        return Err("recording is empty".into());
+    }
+
+    let fs = spec.sample_rate as f64;
+    let stimulus = read_stimulus(options, spec)?;
+    if stimulus.is_empty() {
+        return Err("stimulus is empty".into());
+    }
+
+    let ir_len = match &options.ir_length {
+        Some(cmd) => Some((gen::parse_duration(cmd)? * fs) as usize),
+        None => None,
+    };
+
+    let mut linear = Vec::with_capacity(recording.len());
+    let mut harmonics = vec![Vec::with_capacity(recording.len()); options.harmonics];
+    for ch in recording {
+        let (ir, hd) = deconvolve(options, ch, &stimulus, fs)?;
+        linear.push(ir);
+        for (k, h) in hd.into_iter().enumerate() {
+            harmonics[k].push(h);
+        }
+    }
+
+    truncate_ir(&mut linear, ir_len);
+    for h in harmonics.iter_mut() {
+        truncate_ir(h, ir_len);
+    }
+
+    // the harmonic IRs share the gain of the linear IR to keep the distortion level
+    let max_value = linear.iter()
+        .flatten()
+        .map(|v| v.abs())
+        .fold(0.0, f64::max);
+
+    if options.normalize && max_value > 0.0 {
+        for v in linear.iter_mut().chain(harmonics.iter_mut().flatten()).flatten() {
+            *v *= commands::AMP_MAX / max_value;
+        }
+    }else {
+        let peak = linear.iter()
+            .chain(harmonics.iter().flatten())
+            .flatten()
+            .map(|v| v.abs())
+            .fold(0.0, f64::max);
+        if peak > commands::AMP_MAX {
+            println!("warning: output is clipped (peak {:.2}). use --normalize to avoid clipping.", peak);
+        }
+    }
+
+    Ok((linear, harmonics))
+}
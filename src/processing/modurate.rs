@@ -0,0 +1,153 @@
+use std::f64::consts::PI;
+use hound::WavSpec;
+use rustfft::{FftPlanner, num_complex::Complex, num_traits::Zero};
+use rtaper::{WindowType, TaperSpec};
+
+use crate::commands::modurate::ModOptions;
+use crate::fileio;
+use crate::processing::{self, gen};
+
+// analytic signal of x computed in frequency domain (negative frequencies removed)
+fn analytic_signal(x: &[f64]) -> Vec<Complex<f64>> {
+    let n = x.len();
+    let mut spectrum: Vec<Complex<f64>> = x.iter().map(|&re| Complex::new(re, 0.0)).collect();
+
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(n);
+    fft.process(&mut spectrum);
+
+    for (k, c) in spectrum.iter_mut().enumerate() {
+        if k == 0 || (n.is_multiple_of(2) && k == n / 2) {
+            continue;
+        } else if k < n.div_ceil(2) {
+            *c *= 2.0;
+        } else {
+            *c = Complex::zero();
+        }
+    }
+
+    let ifft = planner.plan_fft_inverse(n);
+    ifft.process(&mut spectrum);
+
+    spectrum.iter().map(|c| c / n as f64).collect()
+}
+
+// shift the phase of every frequency component of the carrier by phase[i]
+pub fn apply_phase(carrier: &[f64], phase: &[f64]) -> Vec<f64> {
+    analytic_signal(carrier)
+        .iter()
+        .zip(phase)
+        .map(|(c, p)| (c * Complex::new(0.0, *p).exp()).re)
+        .collect()
+}
+
+fn modurate_am(carrier: &[f64], modulator: &[f64], depth: f64) -> Vec<f64> {
+    carrier.iter()
+        .zip(modulator)
+        .map(|(c, m)| c * (1.0 + depth * m) / (1.0 + depth))
+        .collect()
+}
+
+fn modurate_ring(carrier: &[f64], modulator: &[f64]) -> Vec<f64> {
+    carrier.iter()
+        .zip(modulator)
+        .map(|(c, m)| c * m)
+        .collect()
+}
+
+fn modurate_pm(carrier: &[f64], modulator: &[f64], index: f64) -> Vec<f64> {
+    let phase: Vec<f64> = modulator.iter().map(|m| index * m).collect();
+    apply_phase(carrier, &phase)
+}
+
+fn modurate_fm(carrier: &[f64], modulator: &[f64], deviation: f64, fs: f64) -> Vec<f64> {
+    let mut acc = 0.0;
+    let phase: Vec<f64> = modulator.iter()
+        .map(|m| {
+            acc += 2.0 * PI * deviation * m / fs;
+            acc
+        })
+        .collect();
+    apply_phase(carrier, &phase)
+}
+
+fn read_modulator(
+    options: &ModOptions,
+    spec: &WavSpec,
+    len: usize,
+) -> Result<Vec<Vec<f64>>, Box<dyn std::error::Error>> {
+    match &options.modulator {
+        Some(filename) => {
+            let (samples, mod_spec) = fileio::read_wav_file(filename)?;
+            if mod_spec.sample_rate != spec.sample_rate {
+                return Err(format!(
+                    "sample rate mismatch: carrier {} Hz, modulator {} Hz",
+                    spec.sample_rate, mod_spec.sample_rate
+                ).into());
+            }
+            Ok(samples)
+        }
+        None => {
+            let fs = spec.sample_rate as f64;
+            let freq = gen::parse_tuned_freq(&options.frequency, &options.tuning)?;
+            let signal_spec = gen::SignalSpec {
+                amp: 1.0,
+                rms: None,
+                ch: "LR".to_string(),
+                fs,
+                // half a sample margin so that exactly `len` samples are generated
+                d: (len as f64 + 0.5) / fs,
+                taper_spec: Some(TaperSpec { taper_type: WindowType::Linear, taper_length: 0 }),
+            };
+            let sine = gen::generate_sine_wave(&signal_spec, processing::value_verify(freq, 0.0, fs / 2.0))?;
+            Ok(vec![sine])
+        }
+    }
+}
+
+pub fn modurate(
+    options: &ModOptions,
+    carrier: &[Vec<f64>],
+    spec: &WavSpec,
+) -> Result<Vec<Vec<f64>>, Box<dyn std::error::Error>> {
+    let len = carrier.first().map(|c| c.len()).unwrap_or(0);
+    let modulator = read_modulator(options, spec, len)?;
+    if modulator.is_empty() {
+        return Err("modulator has no channels".into());
+    }
+    if modulator.len() != 1 && modulator.len() != carrier.len() {
+        return Err(format!(
+            "number of channels mismatch: carrier {}ch, modulator {}ch",
+            carrier.len(), modulator.len()
+        ).into());
+    }
+    let mod_len = modulator.first().map(|m| m.len()).unwrap_or(0);
+    if mod_len < len {
+        return Err(format!("modulator is shorter than the carrier: carrier {} samples, modulator {} samples", len, mod_len).into());
+    }
+
+    let fs = spec.sample_rate as f64;
+    let mut output = Vec::with_capacity(carrier.len());
+
+    for (i, c) in carrier.iter().enumerate() {
+        // a mono modulator is applied to every channel of the carrier
+        let m = &modulator[i % modulator.len()][..c.len()];
+
+        let samples = match options.type_of_modulation.as_str() {
+            "am" => {
+                let depth = processing::value_verify(options.depth, 0.0, 1.0);
+                modurate_am(c, m, depth)
+            }
+            "fm" => {
+                let deviation = options.index * gen::parse_tuned_freq(&options.frequency, &options.tuning)?;
+                modurate_fm(c, m, deviation, fs)
+            }
+            "pm" => { modurate_pm(c, m, options.index) }
+            "ring" => { modurate_ring(c, m) }
+            _ => { return Err("unknown modulation type".into()) }
+        };
+        output.push(samples);
+    }
+
+    Ok(output)
+}